@@ -0,0 +1,50 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day_23::{
+    cliques::{largest_clique, largest_clique_basic},
+    generators::{erdos_renyi, planted_clique},
+    max_clique::{maximum_clique, maximum_clique_parallel},
+    parse_file,
+};
+
+fn bench_largest_clique(c: &mut Criterion) {
+    let mut group = c.benchmark_group("largest_clique");
+    group.sample_size(10);
+
+    let mut graphs = vec![("input".to_string(), parse_file("input.txt"))];
+    for (num_nodes, probability) in [(50, 0.5), (100, 0.3), (100, 0.5)] {
+        graphs.push((
+            format!("gnp_{}_{}", num_nodes, probability),
+            erdos_renyi(num_nodes, probability, 23),
+        ));
+    }
+    graphs.push((
+        "planted_200_0.1_15".to_string(),
+        planted_clique(200, 0.1, 15, 23).graph,
+    ));
+
+    for (name, graph) in &graphs {
+        group.bench_with_input(BenchmarkId::new("basic", name), graph, |b, graph| {
+            b.iter(|| largest_clique_basic(graph))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("pivot_degeneracy", name),
+            graph,
+            |b, graph| b.iter(|| largest_clique(graph)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("branch_and_bound", name),
+            graph,
+            |b, graph| b.iter(|| maximum_clique(graph)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("branch_and_bound_parallel", name),
+            graph,
+            |b, graph| b.iter(|| maximum_clique_parallel(graph)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_largest_clique);
+criterion_main!(benches);
@@ -0,0 +1,132 @@
+/// Fixed-capacity set of dense node ids stored as 64-bit words
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(64)],
+        }
+    }
+
+    pub fn full(capacity: usize) -> Self {
+        let mut result = Self::new(capacity);
+        for idx in 0..capacity as u32 {
+            result.insert(idx);
+        }
+        result
+    }
+
+    pub fn from_ids(capacity: usize, ids: impl IntoIterator<Item = u32>) -> Self {
+        let mut result = Self::new(capacity);
+        for id in ids {
+            result.insert(id);
+        }
+        result
+    }
+
+    pub fn insert(&mut self, id: u32) {
+        self.words[id as usize / 64] |= 1 << (id % 64);
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.words[id as usize / 64] &= !(1 << (id % 64));
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.words
+            .get(id as usize / 64)
+            .is_some_and(|word| word & (1 << (id % 64)) != 0)
+    }
+
+    pub fn first(&self) -> Option<u32> {
+        self.words
+            .iter()
+            .enumerate()
+            .find(|(_, word)| **word != 0)
+            .map(|(idx, word)| idx as u32 * 64 + word.trailing_zeros())
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Keeps only the ids contained in `ids`, which must be sorted ascending
+    pub fn intersection_with_sorted(&self, ids: &[u32]) -> Self {
+        let mut result = Self::new(self.words.len() * 64);
+        for id in ids {
+            if self.contains(*id) {
+                result.insert(*id);
+            }
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(other.words.iter())
+                .map(|(a, b)| a & b)
+                .collect(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros();
+                word &= word - 1;
+                Some(idx as u32 * 64 + bit)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BitSet;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = BitSet::new(130);
+        set.insert(3);
+        set.insert(129);
+        assert!(set.contains(3) && set.contains(129) && !set.contains(4));
+        assert_eq!(set.len(), 2);
+
+        assert_eq!(set.first(), Some(3));
+        set.remove(3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![129]);
+    }
+
+    #[test]
+    fn test_intersection() {
+        let left = BitSet::from_ids(100, [1, 5, 64, 70]);
+        let right = BitSet::from_ids(100, [5, 70, 99]);
+        assert_eq!(
+            left.intersection(&right).iter().collect::<Vec<_>>(),
+            vec![5, 70]
+        );
+        assert_eq!(
+            left.intersection_with_sorted(&[0, 1, 64])
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 64]
+        );
+    }
+}
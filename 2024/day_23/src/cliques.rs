@@ -0,0 +1,461 @@
+use rayon::prelude::*;
+
+use crate::{
+    bitset::BitSet,
+    cores::degeneracy_ordering,
+    graph::{intersect_sorted, Graph},
+};
+
+/// Basic Bron–Kerbosch without pivot selection, kept as a reference for tests and benchmarks
+fn bron_kerbosch(
+    current_clique: &mut Vec<u32>,
+    candidates: &mut BitSet,
+    excluded: &mut BitSet,
+    graph: &Graph,
+    largest_clique: &mut Vec<u32>,
+) {
+    if candidates.is_empty() && excluded.is_empty() {
+        if current_clique.len() > largest_clique.len() {
+            *largest_clique = current_clique.clone();
+        }
+        return;
+    }
+
+    for node in candidates.clone().iter() {
+        let neighbors = graph.neighbours(node);
+
+        current_clique.push(node);
+        let mut new_candidates = candidates.intersection_with_sorted(neighbors);
+        let mut new_excluded = excluded.intersection_with_sorted(neighbors);
+
+        bron_kerbosch(
+            current_clique,
+            &mut new_candidates,
+            &mut new_excluded,
+            graph,
+            largest_clique,
+        );
+
+        current_clique.pop();
+        candidates.remove(node);
+        excluded.insert(node);
+    }
+}
+
+pub fn largest_clique_basic(graph: &Graph) -> Vec<u32> {
+    let mut largest_clique = Vec::new();
+    bron_kerbosch(
+        &mut Vec::new(),
+        &mut BitSet::full(graph.num_nodes()),
+        &mut BitSet::new(graph.num_nodes()),
+        graph,
+        &mut largest_clique,
+    );
+
+    largest_clique
+}
+
+struct Frame {
+    depth: usize,
+    candidates: BitSet,
+    excluded: BitSet,
+    branches: Vec<u32>,
+    next_branch: usize,
+}
+
+/// Lazy iterator over all maximal cliques.
+///
+/// Runs the Tomita et al. variant of Bron–Kerbosch, which only branches on candidates that are
+/// not adjacent to a pivot maximizing the number of its neighbours among the candidates. The
+/// outer level runs over the nodes in degeneracy order (Eppstein, Löffler and Strash) so that
+/// every top-level candidate set has at most `degeneracy` members.
+pub struct MaximalCliques<'a> {
+    graph: &'a Graph,
+    order: Vec<u32>,
+    position: Vec<usize>,
+    next_root: usize,
+    clique: Vec<u32>,
+    stack: Vec<Frame>,
+    min_size: usize,
+    remaining: Option<usize>,
+}
+
+impl<'a> MaximalCliques<'a> {
+    pub fn new(graph: &'a Graph) -> Self {
+        let order = degeneracy_ordering(graph);
+        let mut position = vec![0; graph.num_nodes()];
+        for (idx, node) in order.iter().enumerate() {
+            position[*node as usize] = idx;
+        }
+
+        Self {
+            graph,
+            order,
+            position,
+            next_root: 0,
+            clique: Vec::new(),
+            stack: Vec::new(),
+            min_size: 0,
+            remaining: None,
+        }
+    }
+
+    /// Only yields cliques with at least `min_size` members, smaller ones are pruned early
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Stops after `limit` cliques have been yielded
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.remaining = Some(limit);
+        self
+    }
+
+    /// Starts a new search level below the current clique, returns the clique if it is maximal
+    fn enter(&mut self, candidates: BitSet, excluded: BitSet) -> Option<Vec<u32>> {
+        if candidates.is_empty() {
+            if excluded.is_empty() && self.clique.len() >= self.min_size {
+                return Some(self.clique.clone());
+            }
+            return None;
+        }
+        if self.clique.len() + candidates.len() < self.min_size {
+            return None;
+        }
+
+        let pivot = candidates
+            .iter()
+            .chain(excluded.iter())
+            .max_by_key(|node| {
+                self.graph
+                    .neighbours(*node)
+                    .iter()
+                    .filter(|x| candidates.contains(**x))
+                    .count()
+            })
+            .unwrap();
+        let pivot_neighbours = self.graph.neighbours(pivot);
+        let branches = candidates
+            .iter()
+            .filter(|x| pivot_neighbours.binary_search(x).is_err())
+            .collect();
+
+        self.stack.push(Frame {
+            depth: self.clique.len(),
+            candidates,
+            excluded,
+            branches,
+            next_branch: 0,
+        });
+        None
+    }
+
+    fn next_clique(&mut self) -> Option<Vec<u32>> {
+        loop {
+            let Some(frame) = self.stack.last_mut() else {
+                let root = *self.order.get(self.next_root)?;
+                self.next_root += 1;
+
+                let (later, earlier): (Vec<u32>, Vec<u32>) = self
+                    .graph
+                    .neighbours(root)
+                    .iter()
+                    .partition(|x| self.position[**x as usize] > self.position[root as usize]);
+                self.clique = vec![root];
+                let num_nodes = self.graph.num_nodes();
+                if let Some(clique) = self.enter(
+                    BitSet::from_ids(num_nodes, later),
+                    BitSet::from_ids(num_nodes, earlier),
+                ) {
+                    return Some(clique);
+                }
+                continue;
+            };
+
+            if frame.next_branch == frame.branches.len() {
+                self.stack.pop();
+                continue;
+            }
+
+            let node = frame.branches[frame.next_branch];
+            frame.next_branch += 1;
+
+            let neighbours = self.graph.neighbours(node);
+            let candidates = frame.candidates.intersection_with_sorted(neighbours);
+            let excluded = frame.excluded.intersection_with_sorted(neighbours);
+            frame.candidates.remove(node);
+            frame.excluded.insert(node);
+
+            self.clique.truncate(frame.depth);
+            self.clique.push(node);
+            if let Some(clique) = self.enter(candidates, excluded) {
+                return Some(clique);
+            }
+        }
+    }
+}
+
+impl Iterator for MaximalCliques<'_> {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let clique = self.next_clique()?;
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        Some(clique)
+    }
+}
+
+/// Calls `report` once for every maximal clique
+pub fn for_each_maximal_clique(graph: &Graph, mut report: impl FnMut(&[u32])) {
+    for clique in MaximalCliques::new(graph) {
+        report(&clique);
+    }
+}
+
+pub fn largest_clique(graph: &Graph) -> Vec<u32> {
+    let mut largest_clique = Vec::new();
+    for_each_maximal_clique(graph, |clique| {
+        if clique.len() > largest_clique.len() {
+            largest_clique = clique.to_vec();
+        }
+    });
+
+    largest_clique
+}
+
+/// Neighbours later in `order`, usually the degeneracy order, sorted by id. Orienting every edge this way bounds
+/// each list by the degeneracy and lets every clique be found exactly once.
+pub(crate) fn forward_neighbourhoods(graph: &Graph, order: &[u32]) -> Vec<Vec<u32>> {
+    let mut position = vec![0; graph.num_nodes()];
+    for (idx, node) in order.iter().enumerate() {
+        position[*node as usize] = idx;
+    }
+
+    graph
+        .nodes()
+        .map(|node| {
+            graph
+                .neighbours(node)
+                .iter()
+                .filter(|x| position[**x as usize] > position[node as usize])
+                .copied()
+                .collect()
+        })
+        .collect()
+}
+
+fn extend_k_clique(
+    clique: &mut Vec<u32>,
+    candidates: &[u32],
+    k: usize,
+    forward: &[Vec<u32>],
+    report: &mut dyn FnMut(&[u32]),
+) {
+    if clique.len() == k {
+        let mut sorted_clique = clique.clone();
+        sorted_clique.sort();
+        report(&sorted_clique);
+        return;
+    }
+
+    for node in candidates {
+        clique.push(*node);
+        let new_candidates = intersect_sorted(candidates, &forward[*node as usize]);
+        extend_k_clique(clique, &new_candidates, k, forward, report);
+        clique.pop();
+    }
+}
+
+/// Calls `report` once for every clique with exactly `k` members, ids sorted ascending
+pub fn for_each_k_clique(graph: &Graph, k: usize, mut report: impl FnMut(&[u32])) {
+    if k == 0 {
+        return;
+    }
+    let forward = forward_neighbourhoods(graph, &degeneracy_ordering(graph));
+    for node in graph.nodes() {
+        extend_k_clique(
+            &mut vec![node],
+            &forward[node as usize],
+            k,
+            &forward,
+            &mut report,
+        );
+    }
+}
+
+pub fn k_cliques(graph: &Graph, k: usize) -> Vec<Vec<u32>> {
+    let mut result = Vec::new();
+    for_each_k_clique(graph, k, |clique| result.push(clique.to_vec()));
+    result
+}
+
+fn count_extensions(candidates: &[u32], remaining: usize, forward: &[Vec<u32>]) -> u64 {
+    match remaining {
+        0 => 1,
+        1 => candidates.len() as u64,
+        _ => candidates
+            .iter()
+            .map(|node| {
+                let new_candidates = intersect_sorted(candidates, &forward[*node as usize]);
+                count_extensions(&new_candidates, remaining - 1, forward)
+            })
+            .sum(),
+    }
+}
+
+/// Number of cliques with exactly `k` members, without materializing them
+pub fn count_k_cliques(graph: &Graph, k: usize) -> u64 {
+    if k == 0 {
+        return 0;
+    }
+    let forward = forward_neighbourhoods(graph, &degeneracy_ordering(graph));
+    graph
+        .nodes()
+        .map(|node| count_extensions(&forward[node as usize], k - 1, &forward))
+        .sum()
+}
+
+/// Same cliques in the same order as [`k_cliques`], with the start nodes spread across threads
+pub fn k_cliques_parallel(graph: &Graph, k: usize) -> Vec<Vec<u32>> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let forward = forward_neighbourhoods(graph, &degeneracy_ordering(graph));
+    (0..graph.num_nodes() as u32)
+        .into_par_iter()
+        .flat_map_iter(|node| {
+            let mut result = Vec::new();
+            extend_k_clique(
+                &mut vec![node],
+                &forward[node as usize],
+                k,
+                &forward,
+                &mut |clique| result.push(clique.to_vec()),
+            );
+            result
+        })
+        .collect()
+}
+
+pub fn count_k_cliques_parallel(graph: &Graph, k: usize) -> u64 {
+    if k == 0 {
+        return 0;
+    }
+    let forward = forward_neighbourhoods(graph, &degeneracy_ordering(graph));
+    (0..graph.num_nodes() as u32)
+        .into_par_iter()
+        .map(|node| count_extensions(&forward[node as usize], k - 1, &forward))
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        count_k_cliques, count_k_cliques_parallel, for_each_maximal_clique, k_cliques,
+        k_cliques_parallel, largest_clique, largest_clique_basic, MaximalCliques,
+    };
+    use crate::parse_file;
+
+    #[test]
+    fn test_maximal_cliques() {
+        let graph = parse_file("testinput.txt");
+        let mut found = Vec::new();
+        for_each_maximal_clique(&graph, |clique| {
+            found.push(clique.iter().fold(0u32, |mask, x| mask | (1 << x)))
+        });
+        found.sort();
+
+        // Brute force over all node subsets of the small example
+        let is_clique = |mask: u32| {
+            graph.nodes().all(|left| {
+                mask & (1 << left) == 0
+                    || graph.nodes().all(|right| {
+                        right == left || mask & (1 << right) == 0 || graph.has_edge(left, right)
+                    })
+            })
+        };
+        let expected: Vec<u32> = (1..1u32 << graph.num_nodes())
+            .filter(|mask| {
+                is_clique(*mask)
+                    && graph
+                        .nodes()
+                        .all(|x| mask & (1 << x) != 0 || !is_clique(mask | (1 << x)))
+            })
+            .collect();
+
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_pivot_matches_basic() {
+        let graph = parse_file("input.txt");
+        assert_eq!(
+            largest_clique(&graph).len(),
+            largest_clique_basic(&graph).len()
+        );
+    }
+
+    #[test]
+    fn test_min_size_and_limit() {
+        let graph = parse_file("input.txt");
+        let all: Vec<Vec<u32>> = MaximalCliques::new(&graph).collect();
+        let large: Vec<Vec<u32>> = MaximalCliques::new(&graph).min_size(12).collect();
+        assert_eq!(
+            large,
+            all.iter()
+                .filter(|x| x.len() >= 12)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(MaximalCliques::new(&graph).limit(5).count(), 5);
+        assert_eq!(MaximalCliques::new(&graph).min_size(14).count(), 0);
+    }
+
+    #[test]
+    fn test_k_cliques() {
+        let graph = parse_file("testinput.txt");
+        assert_eq!(count_k_cliques(&graph, 1), 16);
+        assert_eq!(count_k_cliques(&graph, 2), 32);
+        assert_eq!(count_k_cliques(&graph, 3), 12);
+        assert_eq!(count_k_cliques(&graph, 5), 0);
+
+        let names: Vec<Vec<&str>> = k_cliques(&graph, 4)
+            .iter()
+            .map(|x| x.iter().map(|y| graph.name(*y)).collect())
+            .collect();
+        assert_eq!(names, vec![vec!["de", "ka", "co", "ta"]]);
+    }
+
+    #[test]
+    fn test_k_clique_count_matches_enumeration() {
+        let graph = parse_file("input.txt");
+        for k in 3..6 {
+            assert_eq!(
+                count_k_cliques(&graph, k),
+                k_cliques(&graph, k).len() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_k_cliques() {
+        let graph = parse_file("input.txt");
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            assert_eq!(k_cliques_parallel(&graph, 3), k_cliques(&graph, 3));
+            assert_eq!(
+                count_k_cliques_parallel(&graph, 4),
+                count_k_cliques(&graph, 4)
+            );
+        });
+    }
+}
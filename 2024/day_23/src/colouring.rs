@@ -0,0 +1,239 @@
+use std::collections::{BinaryHeap, HashSet};
+
+use serde::Serialize;
+
+use crate::{max_clique::maximum_clique, Graph};
+
+/// Exact colouring is exponential, larger graphs only get the greedy colouring
+pub const MAX_EXACT_COLOURING_NODES: usize = 64;
+
+/// Colour of every node, counted from 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Colouring {
+    colours: Vec<usize>,
+}
+
+impl Colouring {
+    pub fn colour(&self, node: u32) -> usize {
+        self.colours[node as usize]
+    }
+
+    pub fn num_colours(&self) -> usize {
+        self.colours.iter().max().map_or(0, |x| x + 1)
+    }
+
+    /// Nodes of every colour, each class is an independent set
+    pub fn classes(&self) -> Vec<Vec<u32>> {
+        let mut result = vec![Vec::new(); self.num_colours()];
+        for (node, colour) in self.colours.iter().enumerate() {
+            result[*colour].push(node as u32);
+        }
+        result
+    }
+
+    /// No two adjacent nodes share a colour
+    pub fn is_proper(&self, graph: &Graph) -> bool {
+        graph.nodes().all(|node| {
+            graph
+                .neighbours(node)
+                .iter()
+                .all(|x| self.colour(*x) != self.colour(node))
+        })
+    }
+}
+
+/// Brélaz's DSATUR: repeatedly colours the node with the most distinct colours among its
+/// neighbours, preferring higher degree and then lower id, with the smallest free colour.
+/// Members of a clique need distinct colours, so the result bounds the clique size from above.
+/// Nodes are picked from a heap whose outdated entries are skipped, so the run time is
+/// O((n + m) log n) and the colour sets take O(n + m) memory.
+pub fn dsatur(graph: &Graph) -> Colouring {
+    let num_nodes = graph.num_nodes();
+    let mut colours: Vec<Option<usize>> = vec![None; num_nodes];
+    let mut neighbour_colours: Vec<HashSet<usize>> = vec![HashSet::new(); num_nodes];
+    let key = |node: u32, saturation: usize| {
+        (
+            saturation,
+            graph.neighbours(node).len(),
+            std::cmp::Reverse(node),
+        )
+    };
+    let mut queue: BinaryHeap<_> = graph.nodes().map(|x| key(x, 0)).collect();
+
+    while let Some((saturation, _, std::cmp::Reverse(node))) = queue.pop() {
+        let used = &neighbour_colours[node as usize];
+        if colours[node as usize].is_some() || saturation != used.len() {
+            continue;
+        }
+
+        let colour = (0..).find(|x| !used.contains(x)).unwrap();
+        colours[node as usize] = Some(colour);
+        for neighbour in graph.neighbours(node) {
+            let used = &mut neighbour_colours[*neighbour as usize];
+            if colours[*neighbour as usize].is_none() && used.insert(colour) {
+                queue.push(key(*neighbour, used.len()));
+            }
+        }
+    }
+
+    Colouring {
+        colours: colours.into_iter().map(|x| x.unwrap()).collect(),
+    }
+}
+
+/// Backtracking search for a colouring with at most `num_colours` colours. Branches on the most
+/// saturated node and only opens one new colour at a time to avoid permuted colourings.
+fn extend_colouring(graph: &Graph, colours: &mut [Option<usize>], num_colours: usize) -> bool {
+    let saturation = |node: u32| {
+        let mut used: Vec<usize> = graph
+            .neighbours(node)
+            .iter()
+            .filter_map(|x| colours[*x as usize])
+            .collect();
+        used.sort();
+        used.dedup();
+        used.len()
+    };
+    let Some(node) = graph
+        .nodes()
+        .filter(|x| colours[*x as usize].is_none())
+        .max_by_key(|x| (saturation(*x), graph.neighbours(*x).len()))
+    else {
+        return true;
+    };
+
+    let num_used = colours.iter().flatten().max().map_or(0, |x| x + 1);
+    for colour in 0..num_colours.min(num_used + 1) {
+        if graph
+            .neighbours(node)
+            .iter()
+            .any(|x| colours[*x as usize] == Some(colour))
+        {
+            continue;
+        }
+        colours[node as usize] = Some(colour);
+        if extend_colouring(graph, colours, num_colours) {
+            return true;
+        }
+        colours[node as usize] = None;
+    }
+    false
+}
+
+/// Colouring with the chromatic number of colours, or `None` if the graph has more than
+/// [`MAX_EXACT_COLOURING_NODES`] nodes. The search starts at the clique number, which is a
+/// lower bound, and stops below the DSATUR result, which is an upper bound.
+pub fn exact_colouring(graph: &Graph) -> Option<Colouring> {
+    if graph.num_nodes() > MAX_EXACT_COLOURING_NODES {
+        return None;
+    }
+
+    let greedy = dsatur(graph);
+    for num_colours in maximum_clique(graph).len()..greedy.num_colours() {
+        let mut colours = vec![None; graph.num_nodes()];
+        if extend_colouring(graph, &mut colours, num_colours) {
+            return Some(Colouring {
+                colours: colours.into_iter().map(|x| x.unwrap()).collect(),
+            });
+        }
+    }
+    Some(greedy)
+}
+
+/// Partition of the nodes into cliques, obtained by colouring the complement graph where
+/// every colour class is a clique of the original graph. Minimum if `exact` is set and the
+/// graph is small enough for [`exact_colouring`].
+pub fn clique_cover(graph: &Graph, exact: bool) -> Colouring {
+    let complement = graph.complement();
+    if exact {
+        if let Some(colouring) = exact_colouring(&complement) {
+            return colouring;
+        }
+    }
+    dsatur(&complement)
+}
+
+/// Colour classes, or cliques for a clique cover, by node name
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct ColouringReport {
+    pub num_colours: usize,
+    pub classes: Vec<Vec<String>>,
+}
+
+impl ColouringReport {
+    pub fn new(graph: &Graph, colouring: &Colouring) -> Self {
+        Self {
+            num_colours: colouring.num_colours(),
+            classes: colouring
+                .classes()
+                .iter()
+                .map(|class| {
+                    let mut names: Vec<String> =
+                        class.iter().map(|x| graph.name(*x).to_string()).collect();
+                    names.sort();
+                    names
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{clique_cover, dsatur, exact_colouring, ColouringReport};
+    use crate::{generators::erdos_renyi, max_clique::maximum_clique, parse_file, Graph};
+
+    fn cycle(length: usize) -> Graph {
+        let edges: Vec<(String, String)> = (0..length)
+            .map(|idx| (format!("n{idx}"), format!("n{}", (idx + 1) % length)))
+            .collect();
+        Graph::from_edges(&edges)
+    }
+
+    #[test]
+    fn test_dsatur() {
+        let graph = parse_file("input.txt");
+        let colouring = dsatur(&graph);
+        assert!(colouring.is_proper(&graph));
+        assert!(colouring.num_colours() >= maximum_clique(&graph).len());
+
+        // DSATUR is exact on bipartite graphs
+        assert_eq!(dsatur(&cycle(8)).num_colours(), 2);
+    }
+
+    #[test]
+    fn test_exact_colouring() {
+        assert_eq!(exact_colouring(&cycle(5)).unwrap().num_colours(), 3);
+        assert_eq!(exact_colouring(&cycle(6)).unwrap().num_colours(), 2);
+        assert!(exact_colouring(&parse_file("input.txt")).is_none());
+
+        for seed in 0..10 {
+            let graph = erdos_renyi(25, 0.4, seed);
+            let colouring = exact_colouring(&graph).unwrap();
+            assert!(colouring.is_proper(&graph));
+            assert!(colouring.num_colours() <= dsatur(&graph).num_colours());
+            assert!(colouring.num_colours() >= maximum_clique(&graph).len());
+        }
+    }
+
+    #[test]
+    fn test_clique_cover() {
+        let graph = parse_file("testinput.txt");
+        let cover = clique_cover(&graph, true);
+        for class in cover.classes() {
+            for (idx, left) in class.iter().enumerate() {
+                assert!(class[idx + 1..].iter().all(|x| graph.has_edge(*left, *x)));
+            }
+        }
+        assert!(cover.num_colours() <= clique_cover(&graph, false).num_colours());
+
+        // The complement of a five cycle is a five cycle
+        let report = ColouringReport::new(&cycle(5), &clique_cover(&cycle(5), true));
+        assert_eq!(report.num_colours, 3);
+        assert_eq!(report.classes.iter().map(|x| x.len()).sum::<usize>(), 5);
+    }
+}
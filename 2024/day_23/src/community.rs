@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::Graph;
+
+const MAX_LABEL_PROPAGATION_ROUNDS: usize = 100;
+
+/// Assignment of every node to a community. Communities are numbered by the first node that
+/// belongs to them, so equal partitions compare equal regardless of how they were found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    community: Vec<usize>,
+}
+
+impl Partition {
+    pub fn from_labels(labels: &[usize]) -> Self {
+        let mut renumbering = HashMap::new();
+        let community = labels
+            .iter()
+            .map(|label| {
+                let next = renumbering.len();
+                *renumbering.entry(*label).or_insert(next)
+            })
+            .collect();
+        Self { community }
+    }
+
+    pub fn community(&self, node: u32) -> usize {
+        self.community[node as usize]
+    }
+
+    pub fn num_communities(&self) -> usize {
+        self.community.iter().max().map_or(0, |x| x + 1)
+    }
+
+    pub fn communities(&self) -> Vec<Vec<u32>> {
+        let mut result = vec![Vec::new(); self.num_communities()];
+        for (node, community) in self.community.iter().enumerate() {
+            result[*community].push(node as u32);
+        }
+        result
+    }
+
+    /// Newman's modularity, the fraction of edges inside communities minus the fraction
+    /// expected if edges were placed at random with the same node degrees
+    pub fn modularity(&self, graph: &Graph) -> f64 {
+        let num_edges = graph.num_edges() as f64;
+        if num_edges == 0.0 {
+            return 0.0;
+        }
+
+        let mut internal_edges = vec![0.0; self.num_communities()];
+        let mut total_degree = vec![0.0; self.num_communities()];
+        for node in graph.nodes() {
+            let community = self.community(node);
+            total_degree[community] += graph.neighbours(node).len() as f64;
+            for neighbour in graph.neighbours(node) {
+                if node < *neighbour && self.community(*neighbour) == community {
+                    internal_edges[community] += 1.0;
+                }
+            }
+        }
+
+        internal_edges
+            .iter()
+            .zip(total_degree.iter())
+            .map(|(internal, degree)| internal / num_edges - (degree / (2.0 * num_edges)).powi(2))
+            .sum()
+    }
+}
+
+/// Weighted graph on communities of the previous Louvain level. Self loops carry the weight of
+/// the edges that were merged into a single community.
+struct WeightedGraph {
+    neighbours: Vec<Vec<(usize, f64)>>,
+    self_loops: Vec<f64>,
+    total_weight: f64,
+}
+
+impl WeightedGraph {
+    fn from_graph(graph: &Graph) -> Self {
+        Self {
+            neighbours: graph
+                .nodes()
+                .map(|node| {
+                    graph
+                        .neighbours(node)
+                        .iter()
+                        .map(|x| (*x as usize, 1.0))
+                        .collect()
+                })
+                .collect(),
+            self_loops: vec![0.0; graph.num_nodes()],
+            total_weight: graph.num_edges() as f64,
+        }
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.neighbours.len()
+    }
+
+    fn degree(&self, node: usize) -> f64 {
+        self.neighbours[node].iter().map(|(_, w)| w).sum::<f64>() + 2.0 * self.self_loops[node]
+    }
+
+    /// Moves single nodes between communities while that increases modularity.
+    /// Returns the community of every node and whether any node moved.
+    fn local_moving(&self, rng: &mut StdRng) -> (Vec<usize>, bool) {
+        let two_m = 2.0 * self.total_weight;
+        let degrees: Vec<f64> = (0..self.num_nodes()).map(|x| self.degree(x)).collect();
+        let mut community: Vec<usize> = (0..self.num_nodes()).collect();
+        let mut community_degree = degrees.clone();
+        let mut order: Vec<usize> = (0..self.num_nodes()).collect();
+        let mut any_moved = false;
+
+        loop {
+            order.shuffle(rng);
+            let mut moved = false;
+
+            for node in &order {
+                let node = *node;
+                let current = community[node];
+                community_degree[current] -= degrees[node];
+
+                let mut weights: HashMap<usize, f64> = HashMap::new();
+                weights.insert(current, 0.0);
+                for (neighbour, weight) in &self.neighbours[node] {
+                    *weights.entry(community[*neighbour]).or_insert(0.0) += weight;
+                }
+
+                let gain = |target: usize| {
+                    weights[&target] - community_degree[target] * degrees[node] / two_m
+                };
+                let mut best = current;
+                let mut best_gain = gain(current);
+                let mut targets: Vec<usize> = weights.keys().copied().collect();
+                targets.sort();
+                for target in targets {
+                    if gain(target) > best_gain + 1e-12 {
+                        best = target;
+                        best_gain = gain(target);
+                    }
+                }
+
+                community_degree[best] += degrees[node];
+                if best != current {
+                    community[node] = best;
+                    moved = true;
+                    any_moved = true;
+                }
+            }
+
+            if !moved {
+                return (community, any_moved);
+            }
+        }
+    }
+
+    /// Collapses every community into a single node
+    fn aggregate(&self, community: &[usize], num_communities: usize) -> Self {
+        let mut weights: Vec<HashMap<usize, f64>> = vec![HashMap::new(); num_communities];
+        let mut self_loops = vec![0.0; num_communities];
+
+        for (node, neighbours) in self.neighbours.iter().enumerate() {
+            self_loops[community[node]] += self.self_loops[node];
+            for (neighbour, weight) in neighbours {
+                let (left, right) = (community[node], community[*neighbour]);
+                if left == right {
+                    // Every internal edge is seen from both ends
+                    self_loops[left] += weight / 2.0;
+                } else {
+                    *weights[left].entry(right).or_insert(0.0) += weight;
+                }
+            }
+        }
+
+        Self {
+            neighbours: weights
+                .into_iter()
+                .map(|x| {
+                    let mut neighbours: Vec<(usize, f64)> = x.into_iter().collect();
+                    neighbours.sort_by_key(|(neighbour, _)| *neighbour);
+                    neighbours
+                })
+                .collect(),
+            self_loops,
+            total_weight: self.total_weight,
+        }
+    }
+}
+
+/// Louvain method (Blondel et al.): alternates greedy local moving of nodes with aggregation
+/// of the resulting communities until no move improves modularity. `seed` fixes the order in
+/// which nodes are visited.
+pub fn louvain(graph: &Graph, seed: u64) -> Partition {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut membership: Vec<usize> = graph.nodes().map(|x| x as usize).collect();
+    if graph.num_edges() == 0 {
+        return Partition::from_labels(&membership);
+    }
+
+    let mut level = WeightedGraph::from_graph(graph);
+    loop {
+        let (community, moved) = level.local_moving(&mut rng);
+        if !moved {
+            break;
+        }
+
+        // Dense community ids so that the aggregated graph has one node per community
+        let community = Partition::from_labels(&community).community;
+        for x in membership.iter_mut() {
+            *x = community[*x];
+        }
+        let num_communities = community.iter().max().unwrap() + 1;
+        level = level.aggregate(&community, num_communities);
+    }
+
+    Partition::from_labels(&membership)
+}
+
+/// Asynchronous label propagation (Raghavan, Albert and Kumara): every node repeatedly adopts
+/// the most frequent label among its neighbours until labels are stable. Ties are broken at
+/// random, keeping the current label if it is among the most frequent.
+pub fn label_propagation(graph: &Graph, seed: u64) -> Partition {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut labels: Vec<usize> = graph.nodes().map(|x| x as usize).collect();
+    let mut order: Vec<u32> = graph.nodes().collect();
+    let mut counts = vec![0usize; graph.num_nodes()];
+
+    for _ in 0..MAX_LABEL_PROPAGATION_ROUNDS {
+        order.shuffle(&mut rng);
+        let mut changed = false;
+
+        for node in &order {
+            let neighbours = graph.neighbours(*node);
+            if neighbours.is_empty() {
+                continue;
+            }
+            for neighbour in neighbours {
+                counts[labels[*neighbour as usize]] += 1;
+            }
+            let max_count = neighbours
+                .iter()
+                .map(|x| counts[labels[*x as usize]])
+                .max()
+                .unwrap();
+            let mut candidates: Vec<usize> = neighbours
+                .iter()
+                .map(|x| labels[*x as usize])
+                .filter(|x| counts[*x] == max_count)
+                .collect();
+            for neighbour in neighbours {
+                counts[labels[*neighbour as usize]] = 0;
+            }
+
+            let current = labels[*node as usize];
+            if !candidates.contains(&current) {
+                candidates.sort();
+                candidates.dedup();
+                labels[*node as usize] = candidates[rng.gen_range(0..candidates.len())];
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    Partition::from_labels(&labels)
+}
+
+/// Communities reported by node name, largest first
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommunityReport {
+    pub communities: Vec<Vec<String>>,
+    pub modularity: f64,
+}
+
+impl CommunityReport {
+    pub fn new(graph: &Graph, partition: &Partition) -> Self {
+        let mut communities: Vec<Vec<String>> = partition
+            .communities()
+            .iter()
+            .map(|community| {
+                let mut names: Vec<String> = community
+                    .iter()
+                    .map(|x| graph.name(*x).to_string())
+                    .collect();
+                names.sort();
+                names
+            })
+            .collect();
+        communities.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        Self {
+            communities,
+            modularity: partition.modularity(graph),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{label_propagation, louvain, CommunityReport, Partition};
+    use crate::{parse_file, Graph};
+
+    /// Two five node cliques joined by a single edge
+    fn barbell() -> Graph {
+        let mut edges = Vec::new();
+        for offset in [0, 5] {
+            for left in 0..5 {
+                for right in left + 1..5 {
+                    edges.push((
+                        format!("n{}", offset + left),
+                        format!("n{}", offset + right),
+                    ));
+                }
+            }
+        }
+        edges.push(("n4".to_string(), "n5".to_string()));
+        Graph::from_edges(&edges)
+    }
+
+    #[test]
+    fn test_modularity() {
+        let graph = barbell();
+        let split = Partition::from_labels(&[0, 0, 0, 0, 0, 1, 1, 1, 1, 1]);
+        assert!((split.modularity(&graph) - (20.0 / 21.0 - 0.5)).abs() < 1e-12);
+
+        let together = Partition::from_labels(&[0; 10]);
+        assert_eq!(together.modularity(&graph), 0.0);
+    }
+
+    #[test]
+    fn test_barbell_communities() {
+        let graph = barbell();
+        let expected = Partition::from_labels(&[0, 0, 0, 0, 0, 1, 1, 1, 1, 1]);
+        for seed in 0..5 {
+            assert_eq!(louvain(&graph, seed), expected);
+            assert_eq!(label_propagation(&graph, seed), expected);
+        }
+
+        let report = CommunityReport::new(&graph, &expected);
+        assert_eq!(report.communities[0], vec!["n0", "n1", "n2", "n3", "n4"]);
+        let parsed: CommunityReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(parsed, report);
+        assert!((parsed.modularity - (20.0 / 21.0 - 0.5)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_deterministic_seeding() {
+        let graph = parse_file("input.txt");
+        assert_eq!(louvain(&graph, 42), louvain(&graph, 42));
+        assert_eq!(label_propagation(&graph, 42), label_propagation(&graph, 42));
+
+        let louvain_modularity = louvain(&graph, 42).modularity(&graph);
+        let singletons = Partition::from_labels(&(0..graph.num_nodes()).collect::<Vec<_>>());
+        assert!(louvain_modularity > 0.0);
+        assert!(louvain_modularity > singletons.modularity(&graph));
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Graph;
+
+pub fn connected_components(graph: &Graph) -> Vec<Vec<u32>> {
+    let mut visited = vec![false; graph.num_nodes()];
+    let mut components = Vec::new();
+
+    for root in graph.nodes() {
+        if visited[root as usize] {
+            continue;
+        }
+        visited[root as usize] = true;
+        let mut component = Vec::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            component.push(node);
+            for neighbour in graph.neighbours(node) {
+                if !visited[*neighbour as usize] {
+                    visited[*neighbour as usize] = true;
+                    stack.push(*neighbour);
+                }
+            }
+        }
+        component.sort();
+        components.push(component);
+    }
+
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    components
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Biconnectivity {
+    pub bridges: Vec<(u32, u32)>,
+    pub articulation_points: Vec<u32>,
+    pub biconnected_components: Vec<Vec<u32>>,
+}
+
+/// Tarjan's low-link algorithm with an explicit stack, so that deep graphs cannot overflow the
+/// call stack. Biconnected components are collected from a stack of visited edges.
+pub fn biconnectivity(graph: &Graph) -> Biconnectivity {
+    const UNVISITED: u32 = u32::MAX;
+
+    let mut result = Biconnectivity::default();
+    let mut discovery = vec![UNVISITED; graph.num_nodes()];
+    let mut low = vec![0; graph.num_nodes()];
+    let mut is_articulation = vec![false; graph.num_nodes()];
+    let mut timer = 0;
+    let mut edge_stack: Vec<(u32, u32)> = Vec::new();
+
+    for root in graph.nodes() {
+        if discovery[root as usize] != UNVISITED {
+            continue;
+        }
+        discovery[root as usize] = timer;
+        low[root as usize] = timer;
+        timer += 1;
+
+        let mut root_children = 0;
+        // (node, parent, index of the next neighbour to visit)
+        let mut stack: Vec<(u32, Option<u32>, usize)> = vec![(root, None, 0)];
+
+        while let Some((node, parent, next)) = stack.last_mut() {
+            let node = *node;
+            if let Some(neighbour) = graph.neighbours(node).get(*next).copied() {
+                *next += 1;
+                if Some(neighbour) == *parent {
+                    continue;
+                }
+                if discovery[neighbour as usize] == UNVISITED {
+                    discovery[neighbour as usize] = timer;
+                    low[neighbour as usize] = timer;
+                    timer += 1;
+                    edge_stack.push((node, neighbour));
+                    stack.push((neighbour, Some(node), 0));
+                } else if discovery[neighbour as usize] < discovery[node as usize] {
+                    low[node as usize] = low[node as usize].min(discovery[neighbour as usize]);
+                    edge_stack.push((node, neighbour));
+                }
+                continue;
+            }
+
+            let parent = *parent;
+            stack.pop();
+            let Some(parent) = parent else {
+                continue;
+            };
+
+            low[parent as usize] = low[parent as usize].min(low[node as usize]);
+            if low[node as usize] > discovery[parent as usize] {
+                result.bridges.push((parent.min(node), parent.max(node)));
+            }
+            if low[node as usize] >= discovery[parent as usize] {
+                if parent == root {
+                    root_children += 1;
+                } else {
+                    is_articulation[parent as usize] = true;
+                }
+
+                let mut component = Vec::new();
+                while let Some((left, right)) = edge_stack.pop() {
+                    component.push(left);
+                    component.push(right);
+                    if (left, right) == (parent, node) {
+                        break;
+                    }
+                }
+                component.sort();
+                component.dedup();
+                result.biconnected_components.push(component);
+            }
+        }
+
+        if root_children > 1 {
+            is_articulation[root as usize] = true;
+        }
+    }
+
+    result.bridges.sort();
+    result.articulation_points = graph
+        .nodes()
+        .filter(|x| is_articulation[*x as usize])
+        .collect();
+    result.biconnected_components.sort();
+    result
+}
+
+/// Connectivity analysis reported by node name
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectivityReport {
+    pub components: Vec<Vec<String>>,
+    pub component_sizes: Vec<usize>,
+    pub bridges: Vec<(String, String)>,
+    pub articulation_points: Vec<String>,
+    pub biconnected_components: Vec<Vec<String>>,
+}
+
+impl ConnectivityReport {
+    pub fn new(graph: &Graph) -> Self {
+        let names = |nodes: &[u32]| {
+            let mut result: Vec<String> =
+                nodes.iter().map(|x| graph.name(*x).to_string()).collect();
+            result.sort();
+            result
+        };
+
+        let components: Vec<Vec<String>> = connected_components(graph)
+            .iter()
+            .map(|x| names(x))
+            .collect();
+        let biconnectivity = biconnectivity(graph);
+
+        let mut bridges: Vec<(String, String)> = biconnectivity
+            .bridges
+            .iter()
+            .map(|(left, right)| {
+                let pair = names(&[*left, *right]);
+                (pair[0].clone(), pair[1].clone())
+            })
+            .collect();
+        bridges.sort();
+        let mut biconnected_components: Vec<Vec<String>> = biconnectivity
+            .biconnected_components
+            .iter()
+            .map(|x| names(x))
+            .collect();
+        biconnected_components.sort();
+
+        Self {
+            component_sizes: components.iter().map(|x| x.len()).collect(),
+            components,
+            bridges,
+            articulation_points: names(&biconnectivity.articulation_points),
+            biconnected_components,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConnectivityReport;
+    use crate::{parse_file, Graph};
+
+    fn two_triangles_and_an_edge() -> Graph {
+        Graph::from_edges(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "f"),
+            ("f", "d"),
+            ("g", "h"),
+        ])
+    }
+
+    #[test]
+    fn test_components() {
+        let report = ConnectivityReport::new(&two_triangles_and_an_edge());
+        assert_eq!(report.component_sizes, vec![6, 2]);
+        assert_eq!(report.components[1], vec!["g", "h"]);
+    }
+
+    #[test]
+    fn test_bridges_and_articulation_points() {
+        let report = ConnectivityReport::new(&two_triangles_and_an_edge());
+        assert_eq!(
+            report.bridges,
+            vec![
+                ("c".to_string(), "d".to_string()),
+                ("g".to_string(), "h".to_string())
+            ]
+        );
+        assert_eq!(report.articulation_points, vec!["c", "d"]);
+        assert_eq!(
+            report.biconnected_components,
+            vec![
+                vec!["a", "b", "c"],
+                vec!["c", "d"],
+                vec!["d", "e", "f"],
+                vec!["g", "h"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_example_is_biconnected() {
+        let report = ConnectivityReport::new(&parse_file("testinput.txt"));
+        assert_eq!(report.component_sizes, vec![16]);
+        assert!(report.bridges.is_empty() && report.articulation_points.is_empty());
+
+        let parsed: ConnectivityReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::Graph;
+
+/// Matula–Beck peeling that repeatedly removes a node of minimum remaining degree. Returns the
+/// removal order together with the core number of every node, which is the largest minimum
+/// degree seen up to its removal.
+fn peel(graph: &Graph) -> (Vec<u32>, Vec<usize>) {
+    let num_nodes = graph.num_nodes();
+    let mut degrees: Vec<usize> = graph.nodes().map(|x| graph.neighbours(x).len()).collect();
+    let max_degree = degrees.iter().copied().max().unwrap_or(0);
+
+    let mut buckets: Vec<Vec<u32>> = vec![Vec::new(); max_degree + 1];
+    for node in graph.nodes() {
+        buckets[degrees[node as usize]].push(node);
+    }
+
+    let mut removed = vec![false; num_nodes];
+    let mut order = Vec::with_capacity(num_nodes);
+    let mut core_numbers = vec![0; num_nodes];
+    let mut min_degree = 0;
+    let mut current_core = 0;
+
+    while order.len() < num_nodes {
+        // Buckets may hold stale entries of nodes whose degree decreased since insertion
+        let node = loop {
+            match buckets[min_degree].pop() {
+                Some(node) if !removed[node as usize] && degrees[node as usize] == min_degree => {
+                    break node
+                }
+                Some(_) => continue,
+                None => min_degree += 1,
+            }
+        };
+
+        removed[node as usize] = true;
+        order.push(node);
+        current_core = current_core.max(min_degree);
+        core_numbers[node as usize] = current_core;
+        for neighbour in graph.neighbours(node) {
+            let neighbour = *neighbour as usize;
+            if !removed[neighbour] {
+                degrees[neighbour] -= 1;
+                buckets[degrees[neighbour]].push(neighbour as u32);
+                min_degree = min_degree.min(degrees[neighbour]);
+            }
+        }
+    }
+
+    (order, core_numbers)
+}
+
+/// Every node has at most `degeneracy` neighbours later in the returned order
+pub fn degeneracy_ordering(graph: &Graph) -> Vec<u32> {
+    peel(graph).0
+}
+
+/// Largest `k` such that every node has at least `k` neighbours within its `k`-core
+pub fn core_numbers(graph: &Graph) -> Vec<usize> {
+    peel(graph).1
+}
+
+/// Largest `k` for which the graph has a non-empty `k`-core. A clique with `n` members lies in
+/// the `(n - 1)`-core, so no clique can have more than `degeneracy + 1` members.
+pub fn degeneracy(graph: &Graph) -> usize {
+    core_numbers(graph).into_iter().max().unwrap_or(0)
+}
+
+/// Nodes of the maximal subgraph in which every node has at least `k` neighbours
+pub fn k_core(graph: &Graph, k: usize) -> Vec<u32> {
+    let core_numbers = core_numbers(graph);
+    graph
+        .nodes()
+        .filter(|x| core_numbers[*x as usize] >= k)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{core_numbers, degeneracy, degeneracy_ordering, k_core};
+    use crate::{max_clique::maximum_clique, parse_file, Graph};
+
+    #[test]
+    fn test_degeneracy_ordering() {
+        let graph = parse_file("testinput.txt");
+        let order = degeneracy_ordering(&graph);
+        assert_eq!(order.len(), graph.num_nodes());
+
+        let mut position = vec![0; graph.num_nodes()];
+        for (idx, node) in order.iter().enumerate() {
+            position[*node as usize] = idx;
+        }
+        let max_later_neighbours = order
+            .iter()
+            .map(|node| {
+                graph
+                    .neighbours(*node)
+                    .iter()
+                    .filter(|x| position[**x as usize] > position[*node as usize])
+                    .count()
+            })
+            .max();
+        assert_eq!(max_later_neighbours, Some(4));
+    }
+
+    #[test]
+    fn test_core_numbers() {
+        // Triangle with a pendant path attached: the triangle forms the 2-core
+        let graph =
+            Graph::from_edges(&[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "e")]);
+        assert_eq!(core_numbers(&graph), vec![2, 2, 2, 1, 1]);
+        assert_eq!(degeneracy(&graph), 2);
+        assert_eq!(k_core(&graph, 2), vec![0, 1, 2]);
+        assert!(k_core(&graph, 3).is_empty());
+    }
+
+    #[test]
+    fn test_degeneracy_bounds_clique_size() {
+        let graph = parse_file("input.txt");
+        let core_numbers = core_numbers(&graph);
+        let clique = maximum_clique(&graph);
+        assert!(clique.len() <= degeneracy(&graph) + 1);
+        assert!(clique
+            .iter()
+            .all(|x| core_numbers[*x as usize] + 1 >= clique.len()));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    cliques::k_cliques,
+    filter::CliqueFilter,
+    graph::intersect_sorted,
+    max_clique::{maximum_clique, maximum_clique_containing},
+    Graph,
+};
+
+/// Graph under a stream of edge insertions and removals that keeps triangle counts and the
+/// maximum clique up to date.
+///
+/// A changed edge only affects triangles through the common neighbours of its endpoints, so
+/// counts are updated in time proportional to the two neighbour lists. A new edge can only
+/// create larger cliques that contain both endpoints, which is a search restricted to their
+/// common neighbourhood. Removing an edge only forces a full search if it belongs to the
+/// current maximum clique.
+pub struct DynamicGraph {
+    graph: Graph,
+    node_triangles: Vec<u64>,
+    num_triangles: u64,
+    filter: Option<CliqueFilter>,
+    num_matching_triangles: u64,
+    max_clique: Vec<u32>,
+}
+
+impl Default for DynamicGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicGraph {
+    pub fn new() -> Self {
+        Self::from_graph(Graph::new())
+    }
+
+    pub fn from_graph(graph: Graph) -> Self {
+        let mut result = Self {
+            node_triangles: vec![0; graph.num_nodes()],
+            num_triangles: 0,
+            filter: None,
+            num_matching_triangles: 0,
+            max_clique: maximum_clique(&graph),
+            graph,
+        };
+        for triangle in k_cliques(&result.graph, 3) {
+            result.num_triangles += 1;
+            for node in triangle {
+                result.node_triangles[node as usize] += 1;
+            }
+        }
+        result
+    }
+
+    /// Additionally counts the triangles accepted by `filter`, e.g. those containing a node
+    /// starting with "t"
+    pub fn with_filter(mut self, filter: CliqueFilter) -> Self {
+        self.num_matching_triangles =
+            filter.apply(&self.graph, k_cliques(&self.graph, 3)).count() as u64;
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn num_triangles(&self) -> u64 {
+        self.num_triangles
+    }
+
+    /// Number of triangles containing the node, zero for unknown nodes
+    pub fn node_triangles(&self, name: &str) -> u64 {
+        self.graph
+            .id(name)
+            .map_or(0, |id| self.node_triangles[id as usize])
+    }
+
+    /// Number of triangles accepted by the filter set with [`DynamicGraph::with_filter`]
+    pub fn num_matching_triangles(&self) -> u64 {
+        self.num_matching_triangles
+    }
+
+    /// Current maximum clique sorted by name, ties are broken as in [`maximum_clique`]
+    pub fn maximum_clique(&self) -> &[u32] {
+        &self.max_clique
+    }
+
+    fn update_triangles(&mut self, left: u32, right: u32, added: bool) {
+        let common = intersect_sorted(self.graph.neighbours(left), self.graph.neighbours(right));
+        let matching = self.filter.as_ref().map_or(0, |filter| {
+            common
+                .iter()
+                .filter(|x| filter.matches(&self.graph, &[left, right, **x]))
+                .count() as u64
+        });
+
+        let apply = |count: &mut u64, delta: u64| {
+            if added {
+                *count += delta;
+            } else {
+                *count -= delta;
+            }
+        };
+        apply(&mut self.num_triangles, common.len() as u64);
+        apply(&mut self.num_matching_triangles, matching);
+        apply(&mut self.node_triangles[left as usize], common.len() as u64);
+        apply(
+            &mut self.node_triangles[right as usize],
+            common.len() as u64,
+        );
+        for node in common {
+            apply(&mut self.node_triangles[node as usize], 1);
+        }
+    }
+
+    /// Returns false if the edge already existed or is a self loop
+    pub fn add_edge(&mut self, left: &str, right: &str) -> bool {
+        let left = self.graph.intern(left);
+        let right = self.graph.intern(right);
+        self.node_triangles.resize(self.graph.num_nodes(), 0);
+        if self.max_clique.len() <= 1 {
+            // Without edges every single node is a maximum clique, so new nodes may win ties
+            self.max_clique = maximum_clique(&self.graph);
+        }
+        if left == right || self.graph.has_edge(left, right) {
+            return false;
+        }
+
+        self.graph.add_edge_ids(left, right);
+        self.update_triangles(left, right, true);
+
+        let candidate = maximum_clique_containing(&self.graph, &[left, right]);
+        let is_better = candidate.len() > self.max_clique.len()
+            || (candidate.len() == self.max_clique.len()
+                && self.names(&candidate) < self.names(&self.max_clique));
+        if is_better {
+            self.max_clique = candidate;
+        }
+        true
+    }
+
+    /// Returns false if the edge did not exist
+    pub fn remove_edge(&mut self, left: &str, right: &str) -> bool {
+        let (Some(left), Some(right)) = (self.graph.id(left), self.graph.id(right)) else {
+            return false;
+        };
+        if !self.graph.has_edge(left, right) {
+            return false;
+        }
+
+        self.graph.remove_edge_ids(left, right);
+        self.update_triangles(left, right, false);
+
+        if self.max_clique.contains(&left) && self.max_clique.contains(&right) {
+            self.max_clique = maximum_clique(&self.graph);
+        }
+        true
+    }
+
+    fn names(&self, clique: &[u32]) -> Vec<&str> {
+        let mut names: Vec<&str> = clique.iter().map(|x| self.graph.name(*x)).collect();
+        names.sort();
+        names
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::DynamicGraph;
+    use crate::{
+        cliques::{count_k_cliques, k_cliques},
+        filter::{CliqueFilter, NodePredicate, Quantifier},
+        max_clique::maximum_clique,
+        parse_file,
+    };
+
+    fn t_filter() -> CliqueFilter {
+        CliqueFilter::new(NodePredicate::prefix("t"), Quantifier::Any)
+    }
+
+    #[test]
+    fn test_matches_static_example() {
+        let mut dynamic = DynamicGraph::new().with_filter(t_filter());
+        for line in std::fs::read_to_string("testinput.txt").unwrap().lines() {
+            let (left, right) = line.split_once('-').unwrap();
+            dynamic.add_edge(left, right);
+        }
+
+        assert_eq!(dynamic.num_triangles(), 12);
+        assert_eq!(dynamic.num_matching_triangles(), 7);
+        assert_eq!(dynamic.node_triangles("ta"), 3);
+        let names: Vec<&str> = dynamic
+            .maximum_clique()
+            .iter()
+            .map(|x| dynamic.graph().name(*x))
+            .collect();
+        assert_eq!(names, vec!["co", "de", "ka", "ta"]);
+
+        assert!(dynamic.remove_edge("co", "de"));
+        assert!(!dynamic.remove_edge("co", "de"));
+        assert_eq!(dynamic.maximum_clique().len(), 3);
+        assert_eq!(dynamic.num_triangles(), 10);
+    }
+
+    #[test]
+    fn test_random_stream() {
+        let mut rng = StdRng::seed_from_u64(43);
+        let mut dynamic =
+            DynamicGraph::from_graph(parse_file("testinput.txt")).with_filter(t_filter());
+        let names: Vec<String> = dynamic
+            .graph()
+            .nodes()
+            .map(|x| dynamic.graph().name(x).to_string())
+            .collect();
+
+        for _ in 0..300 {
+            let left = &names[rng.gen_range(0..names.len())];
+            let right = &names[rng.gen_range(0..names.len())];
+            if rng.gen_bool(0.6) {
+                dynamic.add_edge(left, right);
+            } else {
+                dynamic.remove_edge(left, right);
+            }
+
+            let graph = dynamic.graph();
+            assert_eq!(dynamic.num_triangles(), count_k_cliques(graph, 3));
+            assert_eq!(
+                dynamic.num_matching_triangles(),
+                t_filter().apply(graph, k_cliques(graph, 3)).count() as u64
+            );
+            assert_eq!(dynamic.maximum_clique(), maximum_clique(graph));
+        }
+    }
+}
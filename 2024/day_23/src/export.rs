@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+
+use crate::{cliques, filter::CliqueFilter, max_clique, Graph};
+
+const CLIQUE_COLOUR: &str = "red";
+const TRIANGLE_COLOUR: &str = "blue";
+
+/// Nodes and edges to colour in exported graphs. Clique colouring takes precedence over
+/// triangle colouring where both apply.
+#[derive(Debug, Clone, Default)]
+pub struct Highlight {
+    clique: HashSet<u32>,
+    triangle_nodes: HashSet<u32>,
+    triangle_edges: HashSet<(u32, u32)>,
+}
+
+impl Highlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_clique(mut self, clique: &[u32]) -> Self {
+        self.clique.extend(clique);
+        self
+    }
+
+    pub fn with_maximum_clique(self, graph: &Graph) -> Self {
+        self.with_clique(&max_clique::maximum_clique(graph))
+    }
+
+    /// Marks all triangles accepted by `filter`, e.g. those touching a node starting with "t"
+    pub fn with_triangles(mut self, graph: &Graph, filter: &CliqueFilter) -> Self {
+        for triangle in filter.apply(graph, cliques::k_cliques(graph, 3)) {
+            self.triangle_nodes.extend(&triangle);
+            for (left, right) in [(0, 1), (1, 2), (0, 2)] {
+                self.triangle_edges
+                    .insert((triangle[left], triangle[right]));
+            }
+        }
+        self
+    }
+
+    fn node_colour(&self, node: u32) -> Option<&'static str> {
+        if self.clique.contains(&node) {
+            Some(CLIQUE_COLOUR)
+        } else if self.triangle_nodes.contains(&node) {
+            Some(TRIANGLE_COLOUR)
+        } else {
+            None
+        }
+    }
+
+    fn edge_colour(&self, left: u32, right: u32) -> Option<&'static str> {
+        if self.clique.contains(&left) && self.clique.contains(&right) {
+            Some(CLIQUE_COLOUR)
+        } else if self
+            .triangle_edges
+            .contains(&(left.min(right), left.max(right)))
+        {
+            Some(TRIANGLE_COLOUR)
+        } else {
+            None
+        }
+    }
+}
+
+fn edges(graph: &Graph) -> impl Iterator<Item = (u32, u32)> + '_ {
+    graph.nodes().flat_map(move |left| {
+        graph
+            .neighbours(left)
+            .iter()
+            .filter(move |right| left < **right)
+            .map(move |right| (left, *right))
+    })
+}
+
+fn escape_dot(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn to_dot(graph: &Graph, highlight: &Highlight) -> String {
+    let mut out_str = "graph G {\nlayout=neato;\n".to_string();
+
+    for node in graph.nodes() {
+        out_str.push_str(&format!("\"{}\"", escape_dot(graph.name(node))));
+        if let Some(colour) = highlight.node_colour(node) {
+            out_str.push_str(&format!(" [style=filled, fillcolor={colour}]"));
+        }
+        out_str.push_str(";\n");
+    }
+
+    for (left, right) in edges(graph) {
+        out_str.push_str(&format!(
+            "\"{}\" -- \"{}\"",
+            escape_dot(graph.name(left)),
+            escape_dot(graph.name(right))
+        ));
+        if let Some(colour) = highlight.edge_colour(left, right) {
+            out_str.push_str(&format!(" [color={colour}, penwidth=2]"));
+        }
+        out_str.push_str(";\n");
+    }
+
+    out_str.push_str("}\n");
+    out_str
+}
+
+fn escape_xml(name: &str) -> String {
+    name.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn push_graphml_element(out_str: &mut String, tag: &str, attributes: &str, colour: Option<&str>) {
+    match colour {
+        Some(colour) => out_str.push_str(&format!(
+            "    <{tag} {attributes}><data key=\"color\">{colour}</data></{tag}>\n"
+        )),
+        None => out_str.push_str(&format!("    <{tag} {attributes}/>\n")),
+    }
+}
+
+/// GraphML document with a `color` attribute on highlighted nodes and edges. It can be read
+/// back with [`crate::formats::InputFormat::GraphMl`].
+pub fn to_graphml(graph: &Graph, highlight: &Highlight) -> String {
+    let mut out_str = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        "  <key id=\"color\" for=\"all\" attr.name=\"color\" attr.type=\"string\"/>\n",
+        "  <graph id=\"G\" edgedefault=\"undirected\">\n",
+    )
+    .to_string();
+
+    for node in graph.nodes() {
+        let attributes = format!("id=\"{}\"", escape_xml(graph.name(node)));
+        push_graphml_element(
+            &mut out_str,
+            "node",
+            &attributes,
+            highlight.node_colour(node),
+        );
+    }
+
+    for (left, right) in edges(graph) {
+        let attributes = format!(
+            "source=\"{}\" target=\"{}\"",
+            escape_xml(graph.name(left)),
+            escape_xml(graph.name(right))
+        );
+        push_graphml_element(
+            &mut out_str,
+            "edge",
+            &attributes,
+            highlight.edge_colour(left, right),
+        );
+    }
+
+    out_str.push_str("  </graph>\n</graphml>\n");
+    out_str
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_dot, to_graphml, Highlight};
+    use crate::{
+        filter::{CliqueFilter, NodePredicate, Quantifier},
+        formats::{parse_graph, InputFormat},
+        parse_file,
+    };
+
+    #[test]
+    fn test_dot() {
+        let graph = parse_file("testinput.txt");
+        let highlight = Highlight::new().with_maximum_clique(&graph);
+        let dot = to_dot(&graph, &highlight);
+
+        assert_eq!(dot.matches(" -- ").count(), graph.num_edges());
+        assert_eq!(dot.matches("fillcolor=red").count(), 4);
+        assert_eq!(dot.matches("color=red, penwidth").count(), 6);
+        assert!(dot.contains("\"ka\" [style=filled, fillcolor=red];"));
+    }
+
+    #[test]
+    fn test_triangles() {
+        let graph = parse_file("testinput.txt");
+        let filter = CliqueFilter::new(NodePredicate::prefix("t"), Quantifier::Any);
+        let highlight = Highlight::new().with_triangles(&graph, &filter);
+        let dot = to_dot(&graph, &highlight);
+
+        assert!(dot.contains("\"tc\" [style=filled, fillcolor=blue];"));
+        assert!(!dot.contains("\"ub\" [style=filled"));
+        assert!(!dot.contains("red"));
+    }
+
+    #[test]
+    fn test_graphml_round_trip() {
+        let graph = parse_file("testinput.txt");
+        let highlight = Highlight::new().with_maximum_clique(&graph);
+        let graphml = to_graphml(&graph, &highlight);
+
+        assert_eq!(
+            graphml.matches("<data key=\"color\">red</data>").count(),
+            10
+        );
+        assert_eq!(parse_graph(&graphml, InputFormat::GraphMl).unwrap(), graph);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use crate::Graph;
+
+/// Condition on a single node name
+pub enum NodePredicate {
+    Prefix(String),
+    Regex(Regex),
+    Set(HashSet<String>),
+    Custom(Box<dyn Fn(&str) -> bool>),
+}
+
+impl NodePredicate {
+    pub fn prefix(prefix: &str) -> Self {
+        NodePredicate::Prefix(prefix.to_string())
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(NodePredicate::Regex(Regex::new(pattern)?))
+    }
+
+    pub fn set<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        NodePredicate::Set(names.into_iter().map(|x| x.to_string()).collect())
+    }
+
+    pub fn from_fn(predicate: impl Fn(&str) -> bool + 'static) -> Self {
+        NodePredicate::Custom(Box::new(predicate))
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NodePredicate::Prefix(prefix) => name.starts_with(prefix.as_str()),
+            NodePredicate::Regex(regex) => regex.is_match(name),
+            NodePredicate::Set(names) => names.contains(name),
+            NodePredicate::Custom(predicate) => predicate(name),
+        }
+    }
+}
+
+/// How many members of a clique have to satisfy the predicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    Any,
+    All,
+    Exactly(usize),
+}
+
+pub struct CliqueFilter {
+    pub predicate: NodePredicate,
+    pub quantifier: Quantifier,
+}
+
+impl CliqueFilter {
+    pub fn new(predicate: NodePredicate, quantifier: Quantifier) -> Self {
+        Self {
+            predicate,
+            quantifier,
+        }
+    }
+
+    pub fn matches(&self, graph: &Graph, clique: &[u32]) -> bool {
+        let mut matching = clique
+            .iter()
+            .map(|x| self.predicate.matches(graph.name(*x)));
+        match self.quantifier {
+            Quantifier::Any => matching.any(|x| x),
+            Quantifier::All => matching.all(|x| x),
+            Quantifier::Exactly(n) => matching.filter(|x| *x).count() == n,
+        }
+    }
+
+    /// Restricts any clique source, e.g. `k_cliques` or `MaximalCliques`, to matching cliques
+    pub fn apply<'a, C: AsRef<[u32]>>(
+        &'a self,
+        graph: &'a Graph,
+        cliques: impl IntoIterator<Item = C> + 'a,
+    ) -> impl Iterator<Item = C> + 'a {
+        cliques
+            .into_iter()
+            .filter(move |clique| self.matches(graph, clique.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CliqueFilter, NodePredicate, Quantifier};
+    use crate::{
+        cliques::{k_cliques, MaximalCliques},
+        parse_file, Graph,
+    };
+
+    #[test]
+    fn test_predicates() {
+        assert!(NodePredicate::prefix("t").matches("tc"));
+        assert!(!NodePredicate::regex("^[a-c]").unwrap().matches("de"));
+        assert!(NodePredicate::set(["co", "de"]).matches("de"));
+        assert!(NodePredicate::from_fn(|x| x.ends_with('a')).matches("ka"));
+    }
+
+    #[test]
+    fn test_quantifiers() {
+        let graph = parse_file("testinput.txt");
+        let triangles = k_cliques(&graph, 3);
+        let count = |predicate: NodePredicate, quantifier: Quantifier| {
+            CliqueFilter::new(predicate, quantifier)
+                .apply(&graph, triangles.iter())
+                .count()
+        };
+
+        assert_eq!(count(NodePredicate::prefix("t"), Quantifier::Any), 7);
+        assert_eq!(count(NodePredicate::prefix("t"), Quantifier::Exactly(0)), 5);
+        assert_eq!(
+            count(
+                NodePredicate::set(["co", "de", "ka", "ta"]),
+                Quantifier::All
+            ),
+            4
+        );
+    }
+
+    #[test]
+    fn test_maximal_clique_filter() {
+        // Maximal cliques abc, bcd and de
+        let graph = Graph::from_edges(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "b"),
+            ("d", "e"),
+        ]);
+        let filter = CliqueFilter::new(NodePredicate::regex("^(a|e)$").unwrap(), Quantifier::Any);
+        let mut cliques: Vec<Vec<&str>> = filter
+            .apply(&graph, MaximalCliques::new(&graph))
+            .map(|clique| {
+                let mut names: Vec<&str> = clique.iter().map(|x| graph.name(*x)).collect();
+                names.sort();
+                names
+            })
+            .collect();
+        cliques.sort();
+        assert_eq!(cliques, vec![vec!["a", "b", "c"], vec!["d", "e"]]);
+    }
+}
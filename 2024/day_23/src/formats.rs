@@ -0,0 +1,415 @@
+use std::{error::Error, fmt};
+
+use regex::Regex;
+
+use crate::{weighted::ConnectionGraph, Graph};
+
+/// Supported textual graph representations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// One `a-b` connection per line, the puzzle input format. Lines may carry a weight as in
+    /// `a-b:12` or be directed as in `a->b`, see [`parse_connections`].
+    Dashed,
+    /// Two node names per line separated by whitespace, commas or semicolons. Further columns,
+    /// e.g. weights, are ignored.
+    EdgeList,
+    /// DIMACS `.clq` with `p edge <nodes> <edges>` and 1-based `e <u> <v>` lines
+    Dimacs,
+    /// `<node id=...>` and `<edge source=... target=...>` elements of a GraphML document
+    GraphMl,
+    /// Square matrix of numbers where any non-zero entry is an edge, optionally preceded by a
+    /// row of node names. Without names, nodes are numbered from 1 as in DIMACS.
+    AdjacencyMatrix,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl FormatError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for FormatError {}
+
+/// Non-empty lines that are not comments, together with their 1-based line numbers
+fn data_lines(contents: &str) -> impl Iterator<Item = (usize, &str)> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#') && !line.starts_with('%'))
+}
+
+fn fields(line: &str) -> Vec<&str> {
+    line.split(|x: char| x.is_whitespace() || x == ',' || x == ';')
+        .filter(|x| !x.is_empty())
+        .collect()
+}
+
+fn is_matrix(rows: &[Vec<&str>]) -> bool {
+    let is_numeric = |row: &Vec<&str>| row.iter().all(|x| x.parse::<f64>().is_ok());
+    let body = match rows.first() {
+        Some(header) if !is_numeric(header) => &rows[1..],
+        _ => rows,
+    };
+    // Two columns are indistinguishable from a numbered edge list
+    body.len() > 2
+        && body
+            .iter()
+            .all(|row| row.len() == body.len() && is_numeric(row))
+}
+
+fn is_dimacs_problem(line: &str) -> bool {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    matches!(tokens[..], ["p", "edge" | "col", _, _])
+}
+
+/// Guesses the format from the first data line, falling back to the shape of the whole input.
+/// DIMACS is only chosen if there is a `p edge` or `p col` problem line, so edge lists with nodes
+/// named `c`, `p` or `e` are not mistaken for it. A two node adjacency matrix looks exactly like a
+/// numbered edge list and is read as the latter.
+pub fn detect_format(contents: &str) -> InputFormat {
+    let Some((_, first)) = data_lines(contents).next() else {
+        return InputFormat::Dashed;
+    };
+
+    if first.starts_with('<') {
+        return InputFormat::GraphMl;
+    }
+    if data_lines(contents).any(|(_, x)| is_dimacs_problem(x)) {
+        return InputFormat::Dimacs;
+    }
+
+    let rows: Vec<Vec<&str>> = data_lines(contents).map(|(_, x)| fields(x)).collect();
+    if is_matrix(&rows) {
+        InputFormat::AdjacencyMatrix
+    } else if rows.iter().all(|x| x.len() == 1) {
+        InputFormat::Dashed
+    } else {
+        InputFormat::EdgeList
+    }
+}
+
+/// Reads `a-b`, `a->b`, `a-b:12` and `a->b:12` lines. Connections without weight get weight 1,
+/// weights have to be finite and non-negative.
+pub fn parse_connections(contents: &str) -> Result<ConnectionGraph, FormatError> {
+    let mut result = ConnectionGraph::new();
+    for (line_number, line) in data_lines(contents) {
+        let (connection, weight) = match line.rsplit_once(':') {
+            Some((connection, weight)) => {
+                let weight: f64 = weight
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|x: &f64| x.is_finite() && *x >= 0.0)
+                    .ok_or_else(|| {
+                        FormatError::new(line_number, format!("invalid weight `{weight}`"))
+                    })?;
+                (connection, weight)
+            }
+            None => (line, 1.0),
+        };
+
+        if let Some((from, to)) = connection.split_once("->") {
+            result.add_arc(from.trim(), to.trim(), weight);
+        } else if let Some((left, right)) = connection.split_once('-') {
+            result.add_edge(left.trim(), right.trim(), weight);
+        } else {
+            return Err(FormatError::new(line_number, "expected `a-b` or `a->b`"));
+        }
+    }
+    Ok(result)
+}
+
+/// Symmetric part of the connections, so a directed connection only counts once it has been
+/// seen in both directions
+fn parse_dashed(contents: &str) -> Result<Graph, FormatError> {
+    if contents.contains("->") || contents.contains(':') {
+        return Ok(parse_connections(contents)?.into_graph());
+    }
+
+    // Plain undirected input, e.g. the puzzle input, needs no arc weights
+    let mut result = Graph::new();
+    for (line_number, line) in data_lines(contents) {
+        let Some((left, right)) = line.split_once('-') else {
+            return Err(FormatError::new(line_number, "expected `a-b` or `a->b`"));
+        };
+        result.add_edge(left.trim(), right.trim());
+    }
+    Ok(result)
+}
+
+fn parse_edge_list(contents: &str) -> Result<Graph, FormatError> {
+    let mut result = Graph::new();
+    for (line_number, line) in data_lines(contents) {
+        match fields(line)[..] {
+            [left, right, ..] => result.add_edge(left, right),
+            _ => return Err(FormatError::new(line_number, "expected two node names")),
+        }
+    }
+    Ok(result)
+}
+
+fn parse_dimacs(contents: &str) -> Result<Graph, FormatError> {
+    let mut result = Graph::new();
+    let mut num_nodes = None;
+
+    for (line_number, line) in data_lines(contents) {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens[..] {
+            ["c", ..] => {}
+            ["p", _, nodes, _] => {
+                let nodes: u32 = nodes
+                    .parse()
+                    .map_err(|_| FormatError::new(line_number, "invalid node count"))?;
+                // Intern all nodes up front so that isolated ones are kept and ids follow numbering
+                for node in 1..=nodes {
+                    result.intern(&node.to_string());
+                }
+                num_nodes = Some(nodes);
+            }
+            ["e", left, right] => {
+                let Some(nodes) = num_nodes else {
+                    return Err(FormatError::new(line_number, "edge before problem line"));
+                };
+                let mut ids = [0; 2];
+                for (id, token) in ids.iter_mut().zip([left, right]) {
+                    *id = token
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|x| (1..=nodes).contains(x))
+                        .ok_or_else(|| FormatError::new(line_number, "invalid node number"))?
+                        - 1;
+                }
+                result.add_edge_ids(ids[0], ids[1]);
+            }
+            _ => return Err(FormatError::new(line_number, "unknown DIMACS line")),
+        }
+    }
+
+    if num_nodes.is_none() {
+        let first_line = data_lines(contents)
+            .next()
+            .map_or(1, |(line_number, _)| line_number);
+        return Err(FormatError::new(first_line, "missing problem line"));
+    }
+    Ok(result)
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Reads the node and edge elements only, so data keys, nested graphs and the `edgedefault`
+/// direction are ignored. Directed edges end up as undirected ones.
+fn parse_graphml(contents: &str) -> Result<Graph, FormatError> {
+    let element = Regex::new(r"<(node|edge)\b([^>]*)>").unwrap();
+    let attribute = Regex::new(r#"\b(id|source|target)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    let mut result = Graph::new();
+    let (mut newlines, mut scanned) = (0, 0);
+    for captures in element.captures_iter(contents) {
+        let start = captures.get(0).unwrap().start();
+        newlines += contents[scanned..start]
+            .bytes()
+            .filter(|x| *x == b'\n')
+            .count();
+        scanned = start;
+        let line_number = newlines + 1;
+        let attributes: Vec<(&str, String)> = attribute
+            .captures_iter(&captures[2])
+            .map(|x| {
+                let value = x.get(2).or(x.get(3)).unwrap().as_str();
+                (x.get(1).unwrap().as_str(), unescape_xml(value))
+            })
+            .collect();
+        let get = |key: &str| {
+            attributes
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.as_str())
+                .ok_or_else(|| FormatError::new(line_number, format!("missing `{key}`")))
+        };
+
+        if &captures[1] == "node" {
+            result.intern(get("id")?);
+        } else {
+            result.add_edge(get("source")?, get("target")?);
+        }
+    }
+    Ok(result)
+}
+
+fn parse_adjacency_matrix(contents: &str) -> Result<Graph, FormatError> {
+    let mut rows: Vec<(usize, Vec<&str>)> = data_lines(contents)
+        .map(|(line_number, line)| (line_number, fields(line)))
+        .collect();
+
+    let has_header = rows
+        .first()
+        .is_some_and(|(_, row)| row.iter().any(|x| x.parse::<f64>().is_err()));
+    let names: Vec<String> = if has_header {
+        rows.remove(0).1.iter().map(|x| x.to_string()).collect()
+    } else {
+        (1..=rows.len()).map(|x| x.to_string()).collect()
+    };
+    if names.len() != rows.len() {
+        return Err(FormatError::new(
+            1,
+            "header does not match the number of rows",
+        ));
+    }
+
+    let mut result = Graph::new();
+    for name in &names {
+        result.intern(name);
+    }
+    for (row_idx, (line_number, row)) in rows.iter().enumerate() {
+        if row.len() != names.len() {
+            return Err(FormatError::new(*line_number, "matrix is not square"));
+        }
+        for (column_idx, entry) in row.iter().enumerate() {
+            let value: f64 = entry
+                .parse()
+                .map_err(|_| FormatError::new(*line_number, format!("invalid entry `{entry}`")))?;
+            // Entries are symmetrised, a one-sided entry is enough for an edge
+            if value != 0.0 {
+                result.add_edge_ids(row_idx as u32, column_idx as u32);
+            }
+        }
+    }
+    Ok(result)
+}
+
+pub fn parse_graph(contents: &str, format: InputFormat) -> Result<Graph, FormatError> {
+    match format {
+        InputFormat::Dashed => parse_dashed(contents),
+        InputFormat::EdgeList => parse_edge_list(contents),
+        InputFormat::Dimacs => parse_dimacs(contents),
+        InputFormat::GraphMl => parse_graphml(contents),
+        InputFormat::AdjacencyMatrix => parse_adjacency_matrix(contents),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{detect_format, parse_graph, InputFormat};
+    use crate::Graph;
+
+    fn edges(graph: &Graph) -> Vec<(String, String)> {
+        let mut result: Vec<(String, String)> = graph
+            .nodes()
+            .flat_map(|left| {
+                graph
+                    .neighbours(left)
+                    .iter()
+                    .filter(move |right| left < **right)
+                    .map(move |right| {
+                        let mut pair = [graph.name(left), graph.name(*right)];
+                        pair.sort();
+                        (pair[0].to_string(), pair[1].to_string())
+                    })
+            })
+            .collect();
+        result.sort();
+        result
+    }
+
+    const DASHED: &str = "1-2\n2-3\n3-1\n3-4\n";
+    const EDGE_LIST: &str = "# comment\n1 2\n2,3\n3;1\n3\t4 0.5\n";
+    const DIMACS: &str = "c triangle with a tail\np edge 4 4\ne 1 2\ne 2 3\ne 3 1\ne 3 4\n";
+    const GRAPHML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <graph id="G" edgedefault="undirected">
+    <node id="1"/><node id="2"/><node id="3"/><node id="4"/>
+    <edge source="1" target="2"/>
+    <edge source="2" target="3"/>
+    <edge id="e3" source='3' target="1"/>
+    <edge source="3" target="4"></edge>
+  </graph>
+</graphml>
+"#;
+    const MATRIX: &str = "0,1,1,0\n1,0,1,0\n1,1,0,1\n0,0,1,0\n";
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(detect_format(DASHED), InputFormat::Dashed);
+        assert_eq!(detect_format(EDGE_LIST), InputFormat::EdgeList);
+        assert_eq!(detect_format(DIMACS), InputFormat::Dimacs);
+        assert_eq!(detect_format(GRAPHML), InputFormat::GraphMl);
+        assert_eq!(detect_format(MATRIX), InputFormat::AdjacencyMatrix);
+        assert_eq!(
+            detect_format("0 1 1\n1 0 0\n1 0 0\n"),
+            InputFormat::AdjacencyMatrix
+        );
+        // Edge lists with nodes named like DIMACS record types
+        assert_eq!(detect_format("c d\nd e\n"), InputFormat::EdgeList);
+        assert_eq!(detect_format("c d\nc e\nc f\n"), InputFormat::EdgeList);
+        assert_eq!(detect_format("p q\ne f\n"), InputFormat::EdgeList);
+        assert_eq!(
+            detect_format("c x\np edge 2 1\ne 1 2\n"),
+            InputFormat::Dimacs
+        );
+    }
+
+    #[test]
+    fn test_formats_agree() {
+        let expected = edges(&parse_graph(DASHED, InputFormat::Dashed).unwrap());
+        assert_eq!(expected.len(), 4);
+        for contents in [EDGE_LIST, DIMACS, GRAPHML, MATRIX] {
+            let graph = parse_graph(contents, detect_format(contents)).unwrap();
+            assert_eq!(graph.num_nodes(), 4);
+            assert_eq!(edges(&graph), expected);
+        }
+    }
+
+    #[test]
+    fn test_matrix_header() {
+        let contents = "a b c\n0 1 0\n0 0 1\n0 0 0\n";
+        assert_eq!(detect_format(contents), InputFormat::AdjacencyMatrix);
+        let graph = parse_graph(contents, InputFormat::AdjacencyMatrix).unwrap();
+        assert_eq!(graph.id("c"), Some(2));
+        assert!(graph.has_edge(1, 0) && graph.has_edge(2, 1) && !graph.has_edge(0, 2));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = parse_graph("ab-cd\nabcd\n", InputFormat::Dashed).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(parse_graph("ab-cd:heavy\n", InputFormat::Dashed).is_err());
+        assert!(parse_graph("p edge 2 1\ne 1 3\n", InputFormat::Dimacs).is_err());
+        let error = parse_graph("\nc no problem line\n", InputFormat::Dimacs).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(parse_graph("a b c\n0 1\n1 0\n", InputFormat::AdjacencyMatrix).is_err());
+        assert!(parse_graph("0 1 0\n1 0\n0 0 0\n", InputFormat::AdjacencyMatrix).is_err());
+        assert!(parse_graph("<edge source=\"a\"/>", InputFormat::GraphMl).is_err());
+        for weight in ["-5", "nan", "inf", "-inf"] {
+            let contents = format!("a-b:1\nb-c:{weight}\n");
+            let error = parse_graph(&contents, InputFormat::Dashed).unwrap_err();
+            assert_eq!(error.line, 2);
+        }
+
+        let graphml = "<graphml>\n  <graph>\n    <node id=\"a\"/>\n    <edge source=\"a\"/>\n";
+        let error = parse_graph(graphml, InputFormat::GraphMl).unwrap_err();
+        assert_eq!(error.line, 4);
+    }
+}
@@ -0,0 +1,199 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::Graph;
+
+/// Lowercase names in the style of the puzzle input, `aa`, `ab`, ... with as many letters as
+/// needed for `num_nodes` distinct names
+pub fn node_names(num_nodes: usize) -> Vec<String> {
+    let mut width = 1;
+    while 26usize.pow(width) < num_nodes {
+        width += 1;
+    }
+    width = width.max(2);
+
+    (0..num_nodes)
+        .map(|idx| {
+            (0..width)
+                .rev()
+                .map(|position| (b'a' + (idx / 26usize.pow(position) % 26) as u8) as char)
+                .collect()
+        })
+        .collect()
+}
+
+fn empty_graph(num_nodes: usize) -> Graph {
+    let mut graph = Graph::new();
+    for name in node_names(num_nodes) {
+        graph.intern(&name);
+    }
+    graph
+}
+
+/// G(n, p): every pair of nodes is connected independently with probability `probability`
+pub fn erdos_renyi(num_nodes: usize, probability: f64, seed: u64) -> Graph {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph = empty_graph(num_nodes);
+    for left in 0..num_nodes as u32 {
+        for right in left + 1..num_nodes as u32 {
+            if rng.gen_bool(probability) {
+                graph.add_edge_ids(left, right);
+            }
+        }
+    }
+    graph
+}
+
+/// Preferential attachment: starts from a clique of `edges_per_node + 1` nodes, then every
+/// new node connects to `edges_per_node` distinct existing nodes chosen with probability
+/// proportional to their degree
+pub fn barabasi_albert(num_nodes: usize, edges_per_node: usize, seed: u64) -> Graph {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph = empty_graph(num_nodes);
+    let initial = (edges_per_node + 1).min(num_nodes) as u32;
+
+    // Every node appears once per incident edge, so uniform draws are degree-proportional
+    let mut endpoints = Vec::new();
+    for left in 0..initial {
+        for right in left + 1..initial {
+            graph.add_edge_ids(left, right);
+            endpoints.extend([left, right]);
+        }
+    }
+
+    for node in initial..num_nodes as u32 {
+        let mut targets: Vec<u32> = Vec::with_capacity(edges_per_node);
+        while targets.len() < edges_per_node {
+            let target = *endpoints.choose(&mut rng).unwrap();
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        for target in targets {
+            graph.add_edge_ids(node, target);
+            endpoints.extend([node, target]);
+        }
+    }
+    graph
+}
+
+/// Random graph with a known clique hidden in it
+pub struct PlantedClique {
+    pub graph: Graph,
+    /// Planted members, sorted by name
+    pub clique: Vec<String>,
+}
+
+/// G(n, p) with `clique_size` randomly chosen nodes made pairwise adjacent. For small `p` the
+/// planted clique is far larger than any clique occurring by chance and therefore the unique
+/// maximum clique.
+pub fn planted_clique(
+    num_nodes: usize,
+    probability: f64,
+    clique_size: usize,
+    seed: u64,
+) -> PlantedClique {
+    let mut graph = erdos_renyi(num_nodes, probability, seed);
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(1));
+    let mut nodes: Vec<u32> = graph.nodes().collect();
+    nodes.shuffle(&mut rng);
+    nodes.truncate(clique_size);
+
+    for (idx, left) in nodes.iter().enumerate() {
+        for right in &nodes[idx + 1..] {
+            graph.add_edge_ids(*left, *right);
+        }
+    }
+
+    let mut clique: Vec<String> = nodes.iter().map(|x| graph.name(*x).to_string()).collect();
+    clique.sort();
+    PlantedClique { graph, clique }
+}
+
+/// One `a-b` line per edge, the format read by [`crate::parse_file`]. Isolated nodes cannot be
+/// represented and are dropped.
+pub fn to_edge_list(graph: &Graph) -> String {
+    let mut out_str = String::new();
+    for left in graph.nodes() {
+        for right in graph.neighbours(left) {
+            if left < *right {
+                out_str.push_str(&format!("{}-{}\n", graph.name(left), graph.name(*right)));
+            }
+        }
+    }
+    out_str
+}
+
+#[cfg(test)]
+mod test {
+    use super::{barabasi_albert, erdos_renyi, node_names, planted_clique, to_edge_list};
+    use crate::{
+        cliques::count_k_cliques,
+        formats::{parse_graph, InputFormat},
+        max_clique::maximum_clique,
+        Graph,
+    };
+
+    fn brute_force_triangles(graph: &Graph) -> u64 {
+        let mut result = 0;
+        for a in graph.nodes() {
+            for b in a + 1..graph.num_nodes() as u32 {
+                for c in b + 1..graph.num_nodes() as u32 {
+                    if graph.has_edge(a, b) && graph.has_edge(b, c) && graph.has_edge(a, c) {
+                        result += 1;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_node_names() {
+        let names = node_names(700);
+        assert_eq!(names[0], "aaa");
+        assert_eq!(names[27], "abb");
+        assert_eq!(node_names(26)[25], "az");
+    }
+
+    #[test]
+    fn test_seeding() {
+        assert_eq!(erdos_renyi(40, 0.3, 7), erdos_renyi(40, 0.3, 7));
+        assert_ne!(erdos_renyi(40, 0.3, 7), erdos_renyi(40, 0.3, 8));
+        assert_eq!(barabasi_albert(40, 3, 7), barabasi_albert(40, 3, 7));
+    }
+
+    #[test]
+    fn test_barabasi_albert() {
+        for seed in 0..10 {
+            let graph = barabasi_albert(100, 3, seed);
+            assert_eq!(graph.num_edges(), 6 + 96 * 3);
+            assert!(graph.nodes().all(|x| graph.neighbours(x).len() >= 3));
+        }
+    }
+
+    #[test]
+    fn test_triangle_counts_match_brute_force() {
+        for seed in 0..20 {
+            let graph = erdos_renyi(40, 0.25, seed);
+            let expected = brute_force_triangles(&graph);
+            assert_eq!(count_k_cliques(&graph, 3), expected);
+
+            let parsed = parse_graph(&to_edge_list(&graph), InputFormat::Dashed).unwrap();
+            assert_eq!(parsed.num_edges(), graph.num_edges());
+            assert_eq!(count_k_cliques(&parsed, 3), expected);
+        }
+    }
+
+    #[test]
+    fn test_planted_clique_is_recovered() {
+        for seed in 0..20 {
+            let planted = planted_clique(80, 0.15, 12, seed);
+            let graph = parse_graph(&to_edge_list(&planted.graph), InputFormat::Dashed).unwrap();
+            let found: Vec<&str> = maximum_clique(&graph)
+                .iter()
+                .map(|x| graph.name(*x))
+                .collect();
+            assert_eq!(found, planted.clique);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+/// Undirected graph with node names interned to dense `u32` ids in order of first appearance.
+/// Neighbours are kept as sorted id vectors so that intersections are linear merges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+    neighbours: Vec<Vec<u32>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Graph with the given edges, nodes are interned in order of first appearance
+    pub fn from_edges<S: AsRef<str>>(edges: &[(S, S)]) -> Self {
+        let mut result = Self::new();
+        for (left, right) in edges {
+            result.add_edge(left.as_ref(), right.as_ref());
+        }
+        result
+    }
+
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.neighbours.push(Vec::new());
+        id
+    }
+
+    pub fn add_edge(&mut self, left: &str, right: &str) {
+        let left = self.intern(left);
+        let right = self.intern(right);
+        self.add_edge_ids(left, right);
+    }
+
+    pub fn add_edge_ids(&mut self, left: u32, right: u32) {
+        if left == right {
+            return;
+        }
+        for (from, to) in [(left, right), (right, left)] {
+            let neighbours = &mut self.neighbours[from as usize];
+            if let Err(pos) = neighbours.binary_search(&to) {
+                neighbours.insert(pos, to);
+            }
+        }
+    }
+
+    /// Returns false if either node is unknown or the edge did not exist
+    pub fn remove_edge(&mut self, left: &str, right: &str) -> bool {
+        match (self.id(left), self.id(right)) {
+            (Some(left), Some(right)) => self.remove_edge_ids(left, right),
+            _ => false,
+        }
+    }
+
+    pub fn remove_edge_ids(&mut self, left: u32, right: u32) -> bool {
+        let mut removed = false;
+        for (from, to) in [(left, right), (right, left)] {
+            let neighbours = &mut self.neighbours[from as usize];
+            if let Ok(pos) = neighbours.binary_search(&to) {
+                neighbours.remove(pos);
+                removed = true;
+            }
+        }
+        removed
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.neighbours.iter().map(|x| x.len()).sum::<usize>() / 2
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = u32> {
+        0..self.names.len() as u32
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn neighbours(&self, id: u32) -> &[u32] {
+        &self.neighbours[id as usize]
+    }
+
+    pub fn has_edge(&self, left: u32, right: u32) -> bool {
+        self.neighbours[left as usize].binary_search(&right).is_ok()
+    }
+
+    /// Graph on the same nodes and ids where two nodes are adjacent exactly if they are not
+    /// adjacent here
+    pub fn complement(&self) -> Graph {
+        let mut result = Graph::new();
+        for name in &self.names {
+            result.intern(name);
+        }
+        for left in self.nodes() {
+            for right in left + 1..self.num_nodes() as u32 {
+                if !self.has_edge(left, right) {
+                    result.add_edge_ids(left, right);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Merges two sorted id slices into their sorted intersection
+pub fn intersect_sorted(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        match left[i].cmp(&right[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                result.push(left[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{intersect_sorted, Graph};
+
+    #[test]
+    fn test_interning() {
+        let mut graph =
+            Graph::from_edges(&[("kh", "tc"), ("qp", "kh"), ("tc", "kh"), ("qp", "qp")]);
+
+        assert_eq!(graph.num_nodes(), 3);
+        assert_eq!(graph.num_edges(), 2);
+        assert_eq!(graph.id("qp"), Some(2));
+        assert_eq!(graph.name(1), "tc");
+        assert_eq!(graph.neighbours(0), &[1, 2]);
+        assert!(graph.has_edge(2, 0) && !graph.has_edge(1, 2));
+
+        assert!(graph.remove_edge("kh", "qp"));
+        assert!(!graph.remove_edge("kh", "qp") && !graph.remove_edge("kh", "xx"));
+        assert_eq!(graph.num_edges(), 1);
+        assert_eq!(graph.num_nodes(), 3);
+
+        let complement = graph.complement();
+        assert_eq!(complement.num_edges(), 2);
+        assert!(complement.has_edge(0, 2) && !complement.has_edge(0, 1));
+    }
+
+    #[test]
+    fn test_intersect_sorted() {
+        assert_eq!(
+            intersect_sorted(&[1, 3, 5, 7], &[2, 3, 4, 7, 9]),
+            vec![3, 7]
+        );
+    }
+}
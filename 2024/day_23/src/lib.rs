@@ -0,0 +1,162 @@
+pub mod bitset;
+pub mod cliques;
+pub mod colouring;
+pub mod community;
+pub mod connectivity;
+pub mod cores;
+pub mod dynamic;
+pub mod export;
+pub mod filter;
+pub mod formats;
+pub mod generators;
+pub mod graph;
+pub mod max_clique;
+pub mod motifs;
+pub mod output;
+pub mod paths;
+pub mod stats;
+pub mod weighted;
+
+use std::{collections::HashSet, fs::read_to_string};
+
+use filter::{CliqueFilter, NodePredicate, Quantifier};
+use formats::{FormatError, InputFormat};
+pub use graph::Graph;
+
+pub fn parse_file(filename: &str) -> Graph {
+    parse_file_with_format(filename, None).expect("Invalid format in file")
+}
+
+/// Reads a graph in the given format, or in the format detected from the contents if `None`
+pub fn parse_file_with_format(
+    filename: &str,
+    format: Option<InputFormat>,
+) -> Result<Graph, FormatError> {
+    let contents = read_to_string(filename).expect("Failed to open file");
+    let format = format.unwrap_or_else(|| formats::detect_format(&contents));
+    formats::parse_graph(&contents, format)
+}
+
+/// Reads `a-b` lines with optional weights and directions, keeping the arcs and their weights
+pub fn parse_connection_file(filename: &str) -> Result<weighted::ConnectionGraph, FormatError> {
+    let contents = read_to_string(filename).expect("Failed to open file");
+    formats::parse_connections(&contents)
+}
+
+pub fn find_size_3_components(graph: &Graph) -> HashSet<(String, String, String)> {
+    cliques::k_cliques_parallel(graph, 3)
+        .iter()
+        .map(|clique| {
+            let mut names: Vec<String> =
+                clique.iter().map(|x| graph.name(*x).to_string()).collect();
+            names.sort();
+            (names[0].clone(), names[1].clone(), names[2].clone())
+        })
+        .collect()
+}
+
+pub fn num_size_3_components_with_t(graph: &Graph) -> u32 {
+    let filter = CliqueFilter::new(NodePredicate::prefix("t"), Quantifier::Any);
+    filter
+        .apply(graph, cliques::k_cliques_parallel(graph, 3))
+        .count() as u32
+}
+
+/// Maximum clique of the network, ties are broken as documented in [`max_clique::maximum_clique`]
+pub fn find_largest_clique(graph: &Graph) -> HashSet<String> {
+    max_clique::maximum_clique_parallel(graph)
+        .iter()
+        .map(|x| graph.name(*x).to_string())
+        .collect()
+}
+
+/// All maximal cliques with at least `min_size` members as sorted node names, largest first
+pub fn find_lan_parties(graph: &Graph, min_size: usize, limit: Option<usize>) -> Vec<Vec<String>> {
+    let mut cliques = cliques::MaximalCliques::new(graph).min_size(min_size);
+    if let Some(limit) = limit {
+        cliques = cliques.limit(limit);
+    }
+
+    let mut result: Vec<Vec<String>> = cliques
+        .map(|clique| {
+            let mut names: Vec<String> =
+                clique.iter().map(|x| graph.name(*x).to_string()).collect();
+            names.sort();
+            names
+        })
+        .collect();
+    result.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+    result
+}
+
+/// Sorted names of the nodes with core number at least `k`
+pub fn find_k_core(graph: &Graph, k: usize) -> Vec<String> {
+    let mut names: Vec<String> = cores::k_core(graph, k)
+        .iter()
+        .map(|x| graph.name(*x).to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Names of the maximum clique sorted and joined by ",", see [`output::CliqueFormatter`] for
+/// other orders, separators and tie policies
+pub fn find_password(graph: &Graph) -> String {
+    output::CliqueFormatter::new().format(graph)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        find_k_core, find_lan_parties, find_largest_clique, find_password, find_size_3_components,
+        num_size_3_components_with_t, parse_file,
+    };
+
+    #[test]
+    fn test_size_3_components() {
+        let graph = parse_file("testinput.txt");
+        assert_eq!(find_size_3_components(&graph).len(), 12);
+    }
+
+    #[test]
+    fn test_size_3_components_with_t() {
+        let graph = parse_file("testinput.txt");
+        assert_eq!(num_size_3_components_with_t(&graph), 7);
+    }
+
+    #[test]
+    fn test_largest_clique() {
+        let graph = parse_file("testinput.txt");
+        let target_set: HashSet<String> = ["co", "de", "ka", "ta"]
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+
+        assert_eq!(find_largest_clique(&graph), target_set);
+    }
+
+    #[test]
+    fn test_password() {
+        let graph = parse_file("testinput.txt");
+        assert_eq!(find_password(&graph), "co,de,ka,ta");
+    }
+
+    #[test]
+    fn test_lan_parties() {
+        let graph = parse_file("testinput.txt");
+        let parties = find_lan_parties(&graph, 3, None);
+        assert_eq!(parties[0], vec!["co", "de", "ka", "ta"]);
+        assert!(parties[1..].iter().all(|x| x.len() == 3));
+        assert_eq!(find_lan_parties(&graph, 4, Some(1)).len(), 1);
+    }
+
+    #[test]
+    fn test_k_core() {
+        let graph = parse_file("testinput.txt");
+        assert_eq!(find_k_core(&graph, 4).len(), graph.num_nodes());
+        assert!(find_k_core(&graph, 5).is_empty());
+    }
+}
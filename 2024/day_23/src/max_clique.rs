@@ -0,0 +1,244 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use crate::{bitset::BitSet, cliques::forward_neighbourhoods, cores::degeneracy_ordering, Graph};
+
+/// Greedy sequential colouring of `candidates`. Returns the nodes ordered by colour class
+/// together with their colour numbers, counted from 1. Nodes sharing a colour are pairwise
+/// non-adjacent, so a clique within the first `i` nodes has at most `colours[i - 1]` members.
+fn colour_sort(graph: &Graph, candidates: &BitSet) -> (Vec<u32>, Vec<usize>) {
+    let mut order = Vec::with_capacity(candidates.len());
+    let mut colours = Vec::with_capacity(candidates.len());
+    let mut uncoloured = candidates.clone();
+    let mut colour = 0;
+
+    while !uncoloured.is_empty() {
+        colour += 1;
+        let mut colour_class = uncoloured.clone();
+        while let Some(node) = colour_class.first() {
+            colour_class.remove(node);
+            for neighbour in graph.neighbours(node) {
+                colour_class.remove(*neighbour);
+            }
+            uncoloured.remove(node);
+            order.push(node);
+            colours.push(colour);
+        }
+    }
+
+    (order, colours)
+}
+
+/// Branch and bound search in the style of Tomita's MCQ using greedy colouring as upper bound.
+/// `bound` holds the largest clique size found by any search sharing it, so that parallel
+/// searches prune each other.
+struct MaximumCliqueSearch<'a> {
+    graph: &'a Graph,
+    bound: &'a AtomicUsize,
+    best: Vec<u32>,
+    best_names: Vec<&'a str>,
+}
+
+impl<'a> MaximumCliqueSearch<'a> {
+    fn new(graph: &'a Graph, bound: &'a AtomicUsize) -> Self {
+        Self {
+            graph,
+            bound,
+            best: Vec::new(),
+            best_names: Vec::new(),
+        }
+    }
+
+    fn bound(&self) -> usize {
+        self.bound.load(Ordering::Relaxed)
+    }
+
+    fn sorted_names(&self, clique: &[u32]) -> Vec<&'a str> {
+        let graph: &'a Graph = self.graph;
+        let mut names: Vec<&'a str> = clique.iter().map(|x| graph.name(*x)).collect();
+        names.sort();
+        names
+    }
+
+    fn consider(&mut self, clique: &[u32]) {
+        if clique.len() < self.best.len().max(self.bound()) {
+            return;
+        }
+        let names = self.sorted_names(clique);
+        if clique.len() > self.best.len() || names < self.best_names {
+            self.best = clique.to_vec();
+            self.best_names = names;
+            self.bound.fetch_max(clique.len(), Ordering::Relaxed);
+        }
+    }
+
+    fn expand(&mut self, clique: &mut Vec<u32>, mut candidates: BitSet) {
+        let (order, colours) = colour_sort(self.graph, &candidates);
+
+        for idx in (0..order.len()).rev() {
+            // Branches that can only tie with the best clique are still explored for tie-breaking
+            if clique.len() + colours[idx] < self.bound() {
+                return;
+            }
+
+            let node = order[idx];
+            clique.push(node);
+            let new_candidates = candidates.intersection_with_sorted(self.graph.neighbours(node));
+            if new_candidates.is_empty() {
+                self.consider(clique);
+            } else {
+                self.expand(clique, new_candidates);
+            }
+            clique.pop();
+            candidates.remove(node);
+        }
+    }
+}
+
+/// Exact maximum clique. If several cliques share the maximum size, the one whose sorted list
+/// of node names is lexicographically smallest wins, independent of the input order.
+pub fn maximum_clique(graph: &Graph) -> Vec<u32> {
+    let bound = AtomicUsize::new(0);
+    let mut search = MaximumCliqueSearch::new(graph, &bound);
+    search.expand(&mut Vec::new(), BitSet::full(graph.num_nodes()));
+
+    let mut result = search.best;
+    result.sort_by_key(|x| graph.name(*x));
+    result
+}
+
+/// Largest clique that contains all of `nodes`, which must form a clique themselves. Ties are
+/// broken as in [`maximum_clique`].
+pub fn maximum_clique_containing(graph: &Graph, nodes: &[u32]) -> Vec<u32> {
+    let mut candidates = BitSet::full(graph.num_nodes());
+    for node in nodes {
+        candidates = candidates.intersection_with_sorted(graph.neighbours(*node));
+    }
+
+    let bound = AtomicUsize::new(0);
+    let mut search = MaximumCliqueSearch::new(graph, &bound);
+    if candidates.is_empty() {
+        search.consider(nodes);
+    } else {
+        search.expand(&mut nodes.to_vec(), candidates);
+    }
+
+    let mut result = search.best;
+    result.sort_by_key(|x| graph.name(*x));
+    result
+}
+
+/// Size of a clique grown greedily from the last node in degeneracy order, which lies in the
+/// densest core. Only used as an initial lower bound.
+fn greedy_clique_size(graph: &Graph, order: &[u32]) -> usize {
+    let mut clique: Vec<u32> = Vec::new();
+    for node in order.iter().rev() {
+        if clique.iter().all(|x| graph.has_edge(*x, *node)) {
+            clique.push(*node);
+        }
+    }
+    clique.len()
+}
+
+/// Same result as [`maximum_clique`], with one search per start node spread across threads.
+/// Each search only extends its start node by neighbours later in degeneracy order, so every
+/// clique is searched from its earliest node. All searches prune against the best size found
+/// by any of them, ties are still explored and resolved when combining the results.
+pub fn maximum_clique_parallel(graph: &Graph) -> Vec<u32> {
+    let order = degeneracy_ordering(graph);
+    let forward = forward_neighbourhoods(graph, &order);
+    let bound = AtomicUsize::new(greedy_clique_size(graph, &order));
+
+    let (mut result, _) = order
+        .par_iter()
+        .map(|root| {
+            let mut search = MaximumCliqueSearch::new(graph, &bound);
+            let later = &forward[*root as usize];
+            if later.len() + 1 >= search.bound() {
+                let candidates = BitSet::from_ids(graph.num_nodes(), later.iter().copied());
+                if candidates.is_empty() {
+                    search.consider(&[*root]);
+                } else {
+                    search.expand(&mut vec![*root], candidates);
+                }
+            }
+            (search.best, search.best_names)
+        })
+        .reduce(
+            || (Vec::new(), Vec::new()),
+            |left, right| {
+                if right.0.len() > left.0.len()
+                    || (right.0.len() == left.0.len() && right.1 < left.1)
+                {
+                    right
+                } else {
+                    left
+                }
+            },
+        );
+
+    result.sort_by_key(|x| graph.name(*x));
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{maximum_clique, maximum_clique_parallel};
+    use crate::{cliques::MaximalCliques, parse_file, Graph};
+
+    #[test]
+    fn test_maximum_clique_size() {
+        let graph = parse_file("input.txt");
+        let largest = MaximalCliques::new(&graph).map(|x| x.len()).max();
+        assert_eq!(Some(maximum_clique(&graph).len()), largest);
+    }
+
+    #[test]
+    fn test_tie_breaking() {
+        let graph = Graph::from_edges(&[
+            ("x", "y"),
+            ("y", "z"),
+            ("x", "z"),
+            ("b", "c"),
+            ("a", "b"),
+            ("c", "a"),
+        ]);
+        let names: Vec<&str> = maximum_clique(&graph)
+            .iter()
+            .map(|x| graph.name(*x))
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(41);
+
+        let mut graphs = vec![parse_file("input.txt"), parse_file("testinput.txt")];
+        // Small dense random graphs have many maximum cliques, which exercises tie-breaking
+        for _ in 0..20 {
+            let mut graph = Graph::new();
+            for left in 0..30 {
+                for right in left + 1..30 {
+                    if rng.gen_bool(0.5) {
+                        graph.add_edge(&format!("n{left}"), &format!("n{right}"));
+                    }
+                }
+            }
+            graphs.push(graph);
+        }
+
+        pool.install(|| {
+            for graph in &graphs {
+                assert_eq!(maximum_clique_parallel(graph), maximum_clique(graph));
+            }
+        });
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+
+use crate::Graph;
+
+/// Node and edge set of an occurrence, which identify it independent of the embedding
+type Occurrence = (Vec<u32>, Vec<(u32, u32)>);
+
+/// Small template graph to search for. Pattern node `i` has id `i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    graph: Graph,
+}
+
+impl Pattern {
+    fn with_nodes(num_nodes: usize) -> Graph {
+        let mut graph = Graph::new();
+        for node in 0..num_nodes {
+            graph.intern(&node.to_string());
+        }
+        graph
+    }
+
+    pub fn from_graph(graph: Graph) -> Self {
+        Self { graph }
+    }
+
+    /// Center `0` connected to `leaves` leaves
+    pub fn star(leaves: usize) -> Self {
+        let mut graph = Self::with_nodes(leaves + 1);
+        for leaf in 1..=leaves as u32 {
+            graph.add_edge_ids(0, leaf);
+        }
+        Self { graph }
+    }
+
+    pub fn path(num_nodes: usize) -> Self {
+        let mut graph = Self::with_nodes(num_nodes);
+        for node in 1..num_nodes as u32 {
+            graph.add_edge_ids(node - 1, node);
+        }
+        Self { graph }
+    }
+
+    pub fn cycle(num_nodes: usize) -> Self {
+        let mut graph = Self::path(num_nodes).graph;
+        if num_nodes > 2 {
+            graph.add_edge_ids(num_nodes as u32 - 1, 0);
+        }
+        Self { graph }
+    }
+
+    pub fn clique(num_nodes: usize) -> Self {
+        let mut graph = Self::with_nodes(num_nodes);
+        for left in 0..num_nodes as u32 {
+            for right in left + 1..num_nodes as u32 {
+                graph.add_edge_ids(left, right);
+            }
+        }
+        Self { graph }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Matching order in which every node after the first has as many already matched
+    /// neighbours as possible, so candidates can be taken from a matched node's neighbours
+    fn matching_order(&self) -> Vec<u32> {
+        let graph = &self.graph;
+        let mut order: Vec<u32> = Vec::with_capacity(graph.num_nodes());
+        let mut matched = vec![false; graph.num_nodes()];
+        while order.len() < graph.num_nodes() {
+            let next = graph
+                .nodes()
+                .filter(|x| !matched[*x as usize])
+                .max_by_key(|x| {
+                    let matched_neighbours = graph
+                        .neighbours(*x)
+                        .iter()
+                        .filter(|y| matched[**y as usize])
+                        .count();
+                    (
+                        matched_neighbours,
+                        graph.neighbours(*x).len(),
+                        std::cmp::Reverse(*x),
+                    )
+                })
+                .unwrap();
+            matched[next as usize] = true;
+            order.push(next);
+        }
+        order
+    }
+}
+
+/// Backtracking matcher in the style of VF2. Pattern nodes are mapped one at a time in a fixed
+/// order, candidates are restricted to neighbours of an already mapped node, and a candidate
+/// is only feasible if its degree suffices and all edges to mapped nodes are preserved. With
+/// `induced` set, non-edges have to be preserved as well.
+struct Matcher<'a> {
+    target: &'a Graph,
+    pattern: &'a Graph,
+    induced: bool,
+    order: Vec<u32>,
+    mapping: Vec<Option<u32>>,
+    used: Vec<bool>,
+}
+
+impl Matcher<'_> {
+    fn is_feasible(&self, node: u32, candidate: u32) -> bool {
+        if self.used[candidate as usize]
+            || self.target.neighbours(candidate).len() < self.pattern.neighbours(node).len()
+        {
+            return false;
+        }
+        self.pattern
+            .nodes()
+            .all(|other| match self.mapping[other as usize] {
+                None => true,
+                Some(mapped) => {
+                    let in_pattern = self.pattern.has_edge(node, other);
+                    let in_target = self.target.has_edge(candidate, mapped);
+                    if in_pattern {
+                        in_target
+                    } else {
+                        !(self.induced && in_target)
+                    }
+                }
+            })
+    }
+
+    fn extend(&mut self, depth: usize, report: &mut dyn FnMut(&[u32])) {
+        if depth == self.order.len() {
+            let mapping: Vec<u32> = self.mapping.iter().map(|x| x.unwrap()).collect();
+            report(&mapping);
+            return;
+        }
+
+        let node = self.order[depth];
+        let anchor = self
+            .pattern
+            .neighbours(node)
+            .iter()
+            .find_map(|x| self.mapping[*x as usize]);
+        let candidates: Vec<u32> = match anchor {
+            Some(anchor) => self.target.neighbours(anchor).to_vec(),
+            None => self.target.nodes().collect(),
+        };
+
+        for candidate in candidates {
+            if !self.is_feasible(node, candidate) {
+                continue;
+            }
+            self.mapping[node as usize] = Some(candidate);
+            self.used[candidate as usize] = true;
+            self.extend(depth + 1, report);
+            self.used[candidate as usize] = false;
+            self.mapping[node as usize] = None;
+        }
+    }
+}
+
+/// Calls `report` for every embedding of the pattern, with the target node of every pattern
+/// node indexed by pattern id. Every occurrence is reported once per pattern automorphism.
+pub fn for_each_embedding(
+    target: &Graph,
+    pattern: &Pattern,
+    induced: bool,
+    mut report: impl FnMut(&[u32]),
+) {
+    let mut matcher = Matcher {
+        target,
+        pattern: &pattern.graph,
+        induced,
+        order: pattern.matching_order(),
+        mapping: vec![None; pattern.graph.num_nodes()],
+        used: vec![false; target.num_nodes()],
+    };
+    matcher.extend(0, &mut report);
+}
+
+pub fn count_embeddings(target: &Graph, pattern: &Pattern, induced: bool) -> u64 {
+    let mut result = 0;
+    for_each_embedding(target, pattern, induced, |_| result += 1);
+    result
+}
+
+/// Number of distinct subgraphs isomorphic to the pattern, e.g. the number of triangles for
+/// [`Pattern::clique`] with 3 nodes
+pub fn count_occurrences(target: &Graph, pattern: &Pattern, induced: bool) -> u64 {
+    let automorphisms = count_embeddings(&pattern.graph, pattern, true);
+    count_embeddings(target, pattern, induced) / automorphisms.max(1)
+}
+
+/// One embedding per distinct occurrence as target node names in pattern node order, sorted
+pub fn find_occurrences(target: &Graph, pattern: &Pattern, induced: bool) -> Vec<Vec<String>> {
+    let mut seen: HashSet<Occurrence> = HashSet::new();
+    let mut result = Vec::new();
+
+    for_each_embedding(target, pattern, induced, |mapping| {
+        let mut nodes = mapping.to_vec();
+        nodes.sort();
+        let mut edges: Vec<(u32, u32)> = pattern
+            .graph
+            .nodes()
+            .flat_map(|left| {
+                pattern.graph.neighbours(left).iter().map(move |right| {
+                    let (a, b) = (mapping[left as usize], mapping[*right as usize]);
+                    (a.min(b), a.max(b))
+                })
+            })
+            .collect();
+        edges.sort();
+        edges.dedup();
+
+        if seen.insert((nodes, edges)) {
+            result.push(
+                mapping
+                    .iter()
+                    .map(|x| target.name(*x).to_string())
+                    .collect::<Vec<_>>(),
+            );
+        }
+    });
+
+    result.sort();
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{count_embeddings, count_occurrences, find_occurrences, Pattern};
+    use crate::{cliques::count_k_cliques, generators::erdos_renyi, parse_file};
+
+    #[test]
+    fn test_triangles_are_special_case() {
+        let graph = parse_file("input.txt");
+        assert_eq!(
+            count_occurrences(&graph, &Pattern::clique(3), false),
+            count_k_cliques(&graph, 3)
+        );
+        assert_eq!(
+            count_embeddings(&graph, &Pattern::cycle(3), false),
+            6 * count_k_cliques(&graph, 3)
+        );
+    }
+
+    #[test]
+    fn test_stars_and_paths() {
+        let graph = parse_file("testinput.txt");
+        let choose = |n: u64, k: u64| (0..k).fold(1, |acc, x| acc * (n - x) / (x + 1));
+        let degrees: Vec<u64> = graph
+            .nodes()
+            .map(|x| graph.neighbours(x).len() as u64)
+            .collect();
+        let triangles = count_k_cliques(&graph, 3);
+
+        let wedges: u64 = degrees.iter().map(|x| choose(*x, 2)).sum();
+        assert_eq!(count_occurrences(&graph, &Pattern::path(3), false), wedges);
+        assert_eq!(
+            count_occurrences(&graph, &Pattern::path(3), true),
+            wedges - 3 * triangles
+        );
+        assert_eq!(
+            count_occurrences(&graph, &Pattern::star(3), false),
+            degrees.iter().map(|x| choose(*x, 3)).sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_four_cycles_match_brute_force() {
+        let mut rng = StdRng::seed_from_u64(49);
+        for _ in 0..5 {
+            let graph = erdos_renyi(14, 0.4, rng.gen());
+            let edge = |a: u32, b: u32| graph.has_edge(a, b);
+            let mut expected = 0;
+            let n = graph.num_nodes() as u32;
+            for a in 0..n {
+                for b in a + 1..n {
+                    for c in b + 1..n {
+                        for d in c + 1..n {
+                            // The three ways to arrange four nodes in a cycle
+                            expected += [(a, b, c, d), (a, b, d, c), (a, c, b, d)]
+                                .iter()
+                                .filter(|(w, x, y, z)| {
+                                    edge(*w, *x) && edge(*x, *y) && edge(*y, *z) && edge(*z, *w)
+                                })
+                                .count() as u64;
+                        }
+                    }
+                }
+            }
+            assert_eq!(
+                count_occurrences(&graph, &Pattern::cycle(4), false),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_occurrences() {
+        let graph = parse_file("testinput.txt");
+        let cliques = find_occurrences(&graph, &Pattern::clique(4), false);
+        assert_eq!(cliques.len(), 1);
+        let mut members = cliques[0].clone();
+        members.sort();
+        assert_eq!(members, vec!["co", "de", "ka", "ta"]);
+
+        // Star occurrences list the center first
+        let stars = find_occurrences(&graph, &Pattern::star(4), false);
+        assert_eq!(
+            stars.len() as u64,
+            count_occurrences(&graph, &Pattern::star(4), false)
+        );
+        assert!(stars.iter().all(|x| x[1..]
+            .iter()
+            .all(|leaf| graph.has_edge(graph.id(&x[0]).unwrap(), graph.id(leaf).unwrap()))));
+    }
+}
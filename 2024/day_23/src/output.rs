@@ -0,0 +1,223 @@
+use crate::{cliques::MaximalCliques, max_clique::maximum_clique_parallel, Graph};
+
+/// Order of the node names within a formatted clique
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeOrder {
+    Name,
+    /// Highest degree first, ties by name
+    Degree,
+    /// Order of first appearance in the input
+    Input,
+}
+
+/// Which cliques to report if several share the maximum size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TiePolicy {
+    /// The first one found by the maximal clique enumeration, which only depends on the input
+    First,
+    /// The one whose sorted list of names is lexicographically smallest
+    LexicographicallySmallest,
+    /// All of them, sorted by their sorted lists of names
+    All,
+}
+
+/// Formats maximum cliques as text or JSON. The default reproduces the puzzle password:
+/// names sorted and joined by ",", lexicographically smallest clique on ties.
+#[derive(Debug, Clone)]
+pub struct CliqueFormatter {
+    order: NodeOrder,
+    separator: String,
+    json: bool,
+    tie_policy: TiePolicy,
+}
+
+impl Default for CliqueFormatter {
+    fn default() -> Self {
+        Self {
+            order: NodeOrder::Name,
+            separator: ",".to_string(),
+            json: false,
+            tie_policy: TiePolicy::LexicographicallySmallest,
+        }
+    }
+}
+
+impl CliqueFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn order(mut self, order: NodeOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Emits JSON arrays of names instead of joined text, the separator is ignored
+    pub fn json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    pub fn tie_policy(mut self, tie_policy: TiePolicy) -> Self {
+        self.tie_policy = tie_policy;
+        self
+    }
+
+    fn ordered_names<'a>(&self, graph: &'a Graph, clique: &[u32]) -> Vec<&'a str> {
+        let mut clique = clique.to_vec();
+        match self.order {
+            NodeOrder::Name => clique.sort_by_key(|x| graph.name(*x)),
+            NodeOrder::Degree => clique.sort_by_key(|x| {
+                (
+                    std::cmp::Reverse(graph.neighbours(*x).len()),
+                    graph.name(*x),
+                )
+            }),
+            NodeOrder::Input => clique.sort(),
+        }
+        clique.iter().map(|x| graph.name(*x)).collect()
+    }
+
+    pub fn format_clique(&self, graph: &Graph, clique: &[u32]) -> String {
+        let names = self.ordered_names(graph, clique);
+        if self.json {
+            serde_json::to_string(&names).unwrap()
+        } else {
+            names.join(&self.separator)
+        }
+    }
+
+    /// Maximum cliques selected by the tie policy
+    pub fn maximum_cliques(&self, graph: &Graph) -> Vec<Vec<u32>> {
+        match self.tie_policy {
+            TiePolicy::LexicographicallySmallest => vec![maximum_clique_parallel(graph)],
+            TiePolicy::First | TiePolicy::All => {
+                let size = maximum_clique_parallel(graph).len();
+                let mut cliques = MaximalCliques::new(graph).min_size(size);
+                if self.tie_policy == TiePolicy::First {
+                    return cliques.next().into_iter().collect();
+                }
+
+                let mut result: Vec<Vec<u32>> = cliques.collect();
+                result.sort_by_cached_key(|clique| {
+                    let mut names: Vec<&str> = clique.iter().map(|x| graph.name(*x)).collect();
+                    names.sort();
+                    names
+                });
+                result
+            }
+        }
+    }
+
+    /// One line per selected maximum clique, or a JSON array with one array per clique
+    pub fn format(&self, graph: &Graph) -> String {
+        let cliques = self.maximum_cliques(graph);
+        if self.json {
+            let names: Vec<Vec<&str>> = cliques
+                .iter()
+                .map(|x| self.ordered_names(graph, x))
+                .collect();
+            serde_json::to_string(&names).unwrap()
+        } else {
+            cliques
+                .iter()
+                .map(|x| self.format_clique(graph, x))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CliqueFormatter, NodeOrder, TiePolicy};
+    use crate::{parse_file, Graph};
+
+    /// Two disjoint triangles, the one with larger names comes first in the input and `z` has an
+    /// extra neighbour `w`
+    fn two_triangles() -> Graph {
+        Graph::from_edges(&[
+            ("x", "y"),
+            ("y", "z"),
+            ("z", "x"),
+            ("z", "w"),
+            ("b", "c"),
+            ("a", "b"),
+            ("c", "a"),
+        ])
+    }
+
+    #[test]
+    fn test_default_is_password() {
+        let graph = parse_file("testinput.txt");
+        assert_eq!(CliqueFormatter::new().format(&graph), "co,de,ka,ta");
+    }
+
+    #[test]
+    fn test_orders_and_separators() {
+        let graph = two_triangles();
+        let clique = [
+            graph.id("z").unwrap(),
+            graph.id("y").unwrap(),
+            graph.id("x").unwrap(),
+        ];
+        let format = |formatter: CliqueFormatter| formatter.format_clique(&graph, &clique);
+
+        assert_eq!(format(CliqueFormatter::new()), "x,y,z");
+        assert_eq!(
+            format(CliqueFormatter::new().order(NodeOrder::Degree)),
+            "z,x,y"
+        );
+        assert_eq!(
+            format(
+                CliqueFormatter::new()
+                    .order(NodeOrder::Input)
+                    .separator(" - ")
+            ),
+            "x - y - z"
+        );
+        assert_eq!(
+            format(CliqueFormatter::new().json(true)),
+            "[\"x\",\"y\",\"z\"]"
+        );
+    }
+
+    #[test]
+    fn test_tie_policies() {
+        let graph = two_triangles();
+        let formatter = CliqueFormatter::new();
+        assert_eq!(formatter.clone().format(&graph), "a,b,c");
+        assert_eq!(
+            formatter.clone().tie_policy(TiePolicy::All).format(&graph),
+            "a,b,c\nx,y,z"
+        );
+        assert_eq!(
+            formatter
+                .clone()
+                .tie_policy(TiePolicy::All)
+                .json(true)
+                .format(&graph),
+            "[[\"a\",\"b\",\"c\"],[\"x\",\"y\",\"z\"]]"
+        );
+
+        // The first clique follows the input structure, not the names: swapping the names of
+        // the two triangles swaps the result
+        let first = formatter.tie_policy(TiePolicy::First);
+        assert_eq!(first.format(&graph), "x,y,z");
+        let relabelled = Graph::from_edges(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "w"),
+            ("y", "z"),
+            ("x", "y"),
+            ("z", "x"),
+        ]);
+        assert_eq!(first.format(&relabelled), "a,b,c");
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Graph;
+
+/// Hop distance from `source` to every node, `None` for unreachable nodes
+pub fn bfs_distances(graph: &Graph, source: u32) -> Vec<Option<u32>> {
+    let mut distances = vec![None; graph.num_nodes()];
+    distances[source as usize] = Some(0);
+    let mut queue = VecDeque::from([source]);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[node as usize].unwrap();
+        for neighbour in graph.neighbours(node) {
+            if distances[*neighbour as usize].is_none() {
+                distances[*neighbour as usize] = Some(distance + 1);
+                queue.push_back(*neighbour);
+            }
+        }
+    }
+    distances
+}
+
+/// Node names along a shortest path including both ends, `None` if either node is unknown or
+/// they are not connected. Among several shortest paths the one through lower ids is taken.
+pub fn shortest_path(graph: &Graph, from: &str, to: &str) -> Option<Vec<String>> {
+    let (from, to) = (graph.id(from)?, graph.id(to)?);
+    let mut parents: Vec<Option<u32>> = vec![None; graph.num_nodes()];
+    let mut visited = vec![false; graph.num_nodes()];
+    visited[from as usize] = true;
+    let mut queue = VecDeque::from([from]);
+
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            break;
+        }
+        for neighbour in graph.neighbours(node) {
+            if !visited[*neighbour as usize] {
+                visited[*neighbour as usize] = true;
+                parents[*neighbour as usize] = Some(node);
+                queue.push_back(*neighbour);
+            }
+        }
+    }
+    if !visited[to as usize] {
+        return None;
+    }
+
+    let mut path = vec![graph.name(to).to_string()];
+    let mut node = to;
+    while let Some(parent) = parents[node as usize] {
+        path.push(graph.name(parent).to_string());
+        node = parent;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Largest distance from every node to any node it can reach
+pub fn eccentricities(graph: &Graph) -> Vec<u32> {
+    graph
+        .nodes()
+        .map(|node| {
+            bfs_distances(graph, node)
+                .into_iter()
+                .flatten()
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+fn is_connected(graph: &Graph) -> bool {
+    graph.num_nodes() == 0 || bfs_distances(graph, 0).iter().all(|x| x.is_some())
+}
+
+/// Largest eccentricity, `None` if the graph is not connected
+pub fn diameter(graph: &Graph) -> Option<u32> {
+    if !is_connected(graph) {
+        return None;
+    }
+    Some(eccentricities(graph).into_iter().max().unwrap_or(0))
+}
+
+/// Smallest eccentricity, `None` if the graph is not connected
+pub fn radius(graph: &Graph) -> Option<u32> {
+    if !is_connected(graph) {
+        return None;
+    }
+    Some(eccentricities(graph).into_iter().min().unwrap_or(0))
+}
+
+/// Fraction of the other nodes every node is connected to
+pub fn degree_centrality(graph: &Graph) -> Vec<f64> {
+    let others = graph.num_nodes().saturating_sub(1).max(1) as f64;
+    graph
+        .nodes()
+        .map(|x| graph.neighbours(x).len() as f64 / others)
+        .collect()
+}
+
+/// Brandes' algorithm. Every node gets the fraction of shortest paths between other pairs of
+/// nodes passing through it, summed over all pairs and normalized by the number of pairs.
+pub fn betweenness_centrality(graph: &Graph) -> Vec<f64> {
+    let num_nodes = graph.num_nodes();
+    let mut centrality = vec![0.0; num_nodes];
+
+    for source in graph.nodes() {
+        let mut order = Vec::with_capacity(num_nodes);
+        let mut num_paths = vec![0.0; num_nodes];
+        let mut distances: Vec<Option<u32>> = vec![None; num_nodes];
+        num_paths[source as usize] = 1.0;
+        distances[source as usize] = Some(0);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            let distance = distances[node as usize].unwrap();
+            for neighbour in graph.neighbours(node) {
+                let neighbour = *neighbour as usize;
+                if distances[neighbour].is_none() {
+                    distances[neighbour] = Some(distance + 1);
+                    queue.push_back(neighbour as u32);
+                }
+                if distances[neighbour] == Some(distance + 1) {
+                    num_paths[neighbour] += num_paths[node as usize];
+                }
+            }
+        }
+
+        // Dependencies accumulate from the farthest nodes back towards the source
+        let mut dependency = vec![0.0; num_nodes];
+        for node in order.iter().rev() {
+            let node = *node as usize;
+            let distance = distances[node].unwrap();
+            for predecessor in graph.neighbours(node as u32) {
+                let predecessor = *predecessor as usize;
+                if distances[predecessor].map(|x| x + 1) == Some(distance) {
+                    dependency[predecessor] +=
+                        num_paths[predecessor] / num_paths[node] * (1.0 + dependency[node]);
+                }
+            }
+            if node != source as usize {
+                centrality[node] += dependency[node];
+            }
+        }
+    }
+
+    // Every undirected pair was counted from both ends
+    let num_pairs = (num_nodes.saturating_sub(1) * num_nodes.saturating_sub(2)) as f64;
+    centrality
+        .into_iter()
+        .map(|x| if num_pairs > 0.0 { x / num_pairs } else { 0.0 })
+        .collect()
+}
+
+/// Inverse mean distance to the reachable nodes, scaled by the fraction of nodes that are
+/// reachable so that nodes in small components do not score highly (Wasserman and Faust)
+pub fn closeness_centrality(graph: &Graph) -> Vec<f64> {
+    let others = graph.num_nodes().saturating_sub(1) as f64;
+    graph
+        .nodes()
+        .map(|node| {
+            let distances: Vec<u32> = bfs_distances(graph, node).into_iter().flatten().collect();
+            let reachable = (distances.len() - 1) as f64;
+            let total: u32 = distances.iter().sum();
+            if total == 0 {
+                return 0.0;
+            }
+            reachable / total as f64 * reachable / others
+        })
+        .collect()
+}
+
+/// Highest scoring nodes by name, ties broken by name
+fn top_n(graph: &Graph, scores: &[f64], n: usize) -> Vec<(String, f64)> {
+    let mut ranked: Vec<(String, f64)> = graph
+        .nodes()
+        .map(|x| (graph.name(x).to_string(), scores[x as usize]))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(n);
+    ranked
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct CentralityReport {
+    pub diameter: Option<u32>,
+    pub radius: Option<u32>,
+    pub degree: Vec<(String, f64)>,
+    pub betweenness: Vec<(String, f64)>,
+    pub closeness: Vec<(String, f64)>,
+}
+
+impl CentralityReport {
+    /// Top `n` nodes for every centrality measure
+    pub fn new(graph: &Graph, n: usize) -> Self {
+        Self {
+            diameter: diameter(graph),
+            radius: radius(graph),
+            degree: top_n(graph, &degree_centrality(graph), n),
+            betweenness: top_n(graph, &betweenness_centrality(graph), n),
+            closeness: top_n(graph, &closeness_centrality(graph), n),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        betweenness_centrality, closeness_centrality, diameter, eccentricities, radius,
+        shortest_path, CentralityReport,
+    };
+    use crate::{parse_file, Graph};
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-12, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_path_graph() {
+        let graph = Graph::from_edges(&[("a", "b"), ("b", "c"), ("c", "d")]);
+        assert_eq!(
+            shortest_path(&graph, "a", "d").unwrap(),
+            vec!["a", "b", "c", "d"]
+        );
+        assert_eq!(eccentricities(&graph), vec![3, 2, 2, 3]);
+        assert_eq!((diameter(&graph), radius(&graph)), (Some(3), Some(2)));
+        assert_close(
+            &betweenness_centrality(&graph),
+            &[0.0, 2.0 / 3.0, 2.0 / 3.0, 0.0],
+        );
+        assert_close(&closeness_centrality(&graph), &[0.5, 0.75, 0.75, 0.5]);
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let graph = Graph::from_edges(&[("a", "b"), ("b", "c"), ("x", "y")]);
+        assert_eq!(shortest_path(&graph, "a", "x"), None);
+        assert_eq!(shortest_path(&graph, "a", "unknown"), None);
+        assert_eq!(diameter(&graph), None);
+        // b lies on both directions of the only path between a and c, out of 4 * 3 ordered pairs
+        assert_close(
+            &betweenness_centrality(&graph)[..3],
+            &[0.0, 2.0 / 12.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let graph = parse_file("testinput.txt");
+        let report = CentralityReport::new(&graph, 3);
+        assert_eq!(report.degree.len(), 3);
+        assert_eq!(report.diameter, eccentricities(&graph).into_iter().max());
+        assert!(report.betweenness.windows(2).all(|x| x[0].1 >= x[1].1));
+
+        // Parsed floats may differ from the printed ones in the last bit
+        let parsed: CentralityReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(
+            (parsed.diameter, parsed.radius),
+            (report.diameter, report.radius)
+        );
+        for (parsed, expected) in [
+            (&parsed.degree, &report.degree),
+            (&parsed.betweenness, &report.betweenness),
+            (&parsed.closeness, &report.closeness),
+        ] {
+            let names =
+                |list: &[(String, f64)]| list.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
+            let values = |list: &[(String, f64)]| list.iter().map(|x| x.1).collect::<Vec<_>>();
+            assert_eq!(names(parsed), names(expected));
+            assert_close(&values(parsed), &values(expected));
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cliques::forward_neighbourhoods,
+    cores::degeneracy_ordering,
+    graph::{intersect_sorted, Graph},
+};
+
+/// Summary statistics of a graph
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct GraphStats {
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    /// Fraction of all node pairs that are connected
+    pub density: f64,
+    pub min_degree: usize,
+    pub max_degree: usize,
+    pub mean_degree: f64,
+    /// Number of nodes for every occurring degree
+    pub degree_histogram: BTreeMap<usize, usize>,
+    pub num_triangles: u64,
+    /// Fraction of paths of length two that are closed to a triangle (transitivity)
+    pub global_clustering: f64,
+    /// Mean of the local clustering coefficients
+    pub average_clustering: f64,
+    /// Fraction of pairs of neighbours that are connected, for every node by name
+    pub local_clustering: BTreeMap<String, f64>,
+    /// Pearson correlation of the degrees at both ends of an edge, `None` if all edges join
+    /// nodes of equal degree
+    pub assortativity: Option<f64>,
+}
+
+fn degree_assortativity(graph: &Graph) -> Option<f64> {
+    let mut num_edges = 0.0;
+    let (mut product, mut sum, mut sum_of_squares) = (0.0, 0.0, 0.0);
+    for left in graph.nodes() {
+        for right in graph.neighbours(left) {
+            if left < *right {
+                let j = graph.neighbours(left).len() as f64;
+                let k = graph.neighbours(*right).len() as f64;
+                num_edges += 1.0;
+                product += j * k;
+                sum += (j + k) / 2.0;
+                sum_of_squares += (j * j + k * k) / 2.0;
+            }
+        }
+    }
+
+    let mean_squared = (sum / num_edges).powi(2);
+    let variance = sum_of_squares / num_edges - mean_squared;
+    if num_edges == 0.0 || variance.abs() < 1e-12 {
+        return None;
+    }
+    Some((product / num_edges - mean_squared) / variance)
+}
+
+impl GraphStats {
+    pub fn new(graph: &Graph) -> Self {
+        let num_nodes = graph.num_nodes();
+        let num_edges = graph.num_edges();
+        let degrees: Vec<usize> = graph.nodes().map(|x| graph.neighbours(x).len()).collect();
+
+        let mut degree_histogram = BTreeMap::new();
+        for degree in &degrees {
+            *degree_histogram.entry(*degree).or_insert(0) += 1;
+        }
+
+        // Every triangle is found once, from its first node along the degeneracy order
+        let mut node_triangles = vec![0u64; num_nodes];
+        let mut num_triangles = 0;
+        let forward = forward_neighbourhoods(graph, &degeneracy_ordering(graph));
+        for first in graph.nodes() {
+            for second in &forward[first as usize] {
+                for third in intersect_sorted(&forward[first as usize], &forward[*second as usize])
+                {
+                    for node in [first, *second, third] {
+                        node_triangles[node as usize] += 1;
+                    }
+                    num_triangles += 1;
+                }
+            }
+        }
+
+        let wedges = |degree: usize| (degree * degree.saturating_sub(1) / 2) as f64;
+        let local: Vec<f64> = graph
+            .nodes()
+            .map(|x| {
+                let degree = degrees[x as usize];
+                if degree < 2 {
+                    0.0
+                } else {
+                    node_triangles[x as usize] as f64 / wedges(degree)
+                }
+            })
+            .collect();
+        let total_wedges: f64 = degrees.iter().map(|x| wedges(*x)).sum();
+
+        let num_pairs = (num_nodes * num_nodes.saturating_sub(1) / 2) as f64;
+        Self {
+            num_nodes,
+            num_edges,
+            density: if num_pairs > 0.0 {
+                num_edges as f64 / num_pairs
+            } else {
+                0.0
+            },
+            min_degree: degrees.iter().copied().min().unwrap_or(0),
+            max_degree: degrees.iter().copied().max().unwrap_or(0),
+            mean_degree: if num_nodes > 0 {
+                2.0 * num_edges as f64 / num_nodes as f64
+            } else {
+                0.0
+            },
+            degree_histogram,
+            num_triangles,
+            global_clustering: if total_wedges > 0.0 {
+                3.0 * num_triangles as f64 / total_wedges
+            } else {
+                0.0
+            },
+            average_clustering: if num_nodes > 0 {
+                local.iter().sum::<f64>() / num_nodes as f64
+            } else {
+                0.0
+            },
+            local_clustering: graph
+                .nodes()
+                .map(|x| (graph.name(x).to_string(), local[x as usize]))
+                .collect(),
+            assortativity: degree_assortativity(graph),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Aligned plain text table of the summary values and the degree histogram. Local
+    /// clustering coefficients are only part of the JSON output.
+    pub fn to_table(&self) -> String {
+        let mut rows: Vec<(String, String)> = vec![
+            ("nodes".to_string(), self.num_nodes.to_string()),
+            ("edges".to_string(), self.num_edges.to_string()),
+            ("density".to_string(), format!("{:.4}", self.density)),
+            (
+                "degree (min/mean/max)".to_string(),
+                format!(
+                    "{} / {:.2} / {}",
+                    self.min_degree, self.mean_degree, self.max_degree
+                ),
+            ),
+            ("triangles".to_string(), self.num_triangles.to_string()),
+            (
+                "global clustering".to_string(),
+                format!("{:.4}", self.global_clustering),
+            ),
+            (
+                "average clustering".to_string(),
+                format!("{:.4}", self.average_clustering),
+            ),
+            (
+                "assortativity".to_string(),
+                self.assortativity
+                    .map_or("undefined".to_string(), |x| format!("{:.4}", x)),
+            ),
+        ];
+        for (degree, count) in &self.degree_histogram {
+            rows.push((format!("nodes with degree {}", degree), count.to_string()));
+        }
+
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        rows.iter()
+            .map(|(name, value)| format!("{:<width$}  {}\n", name, value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GraphStats;
+    use crate::{cliques::count_k_cliques, generators::erdos_renyi, parse_file, Graph};
+
+    #[test]
+    fn test_example_stats() {
+        let stats = GraphStats::new(&parse_file("testinput.txt"));
+        assert_eq!((stats.num_nodes, stats.num_edges), (16, 32));
+        assert_eq!(
+            stats.degree_histogram.into_iter().collect::<Vec<_>>(),
+            vec![(4, 16)]
+        );
+        assert_eq!(stats.num_triangles, 12);
+        // 12 triangles close 36 of the 16 * 6 paths of length two
+        assert!((stats.global_clustering - 0.375).abs() < 1e-12);
+        assert!((stats.density - 32.0 / 120.0).abs() < 1e-12);
+        // Every node has degree 4
+        assert_eq!(stats.assortativity, None);
+        assert_eq!(stats.local_clustering["ka"], 0.5);
+    }
+
+    #[test]
+    fn test_triangle_counts() {
+        for seed in 0..5 {
+            let graph = erdos_renyi(40, 0.3, seed);
+            let stats = GraphStats::new(&graph);
+            assert_eq!(stats.num_triangles, count_k_cliques(&graph, 3));
+        }
+    }
+
+    #[test]
+    fn test_star_is_disassortative() {
+        let graph = Graph::from_edges(&[("a", "b"), ("a", "c"), ("a", "d")]);
+        let stats = GraphStats::new(&graph);
+        assert!((stats.assortativity.unwrap() + 1.0).abs() < 1e-12);
+        assert_eq!(stats.average_clustering, 0.0);
+    }
+
+    #[test]
+    fn test_output() {
+        let stats = GraphStats::new(&parse_file("testinput.txt"));
+        let table = stats.to_table();
+        assert!(table
+            .lines()
+            .any(|x| x.starts_with("nodes with degree 4 ") && x.ends_with(" 16")));
+        assert!(table
+            .lines()
+            .any(|x| x.starts_with("edges ") && x.ends_with(" 32")));
+
+        // Parsed floats may differ from the printed ones in the last bit
+        let parsed: GraphStats = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(
+            (parsed.num_nodes, parsed.num_edges, parsed.num_triangles),
+            (stats.num_nodes, stats.num_edges, stats.num_triangles)
+        );
+        assert_eq!(parsed.degree_histogram, stats.degree_histogram);
+        assert_eq!(parsed.assortativity, None);
+        assert!((parsed.global_clustering - stats.global_clustering).abs() < 1e-12);
+        assert!((parsed.density - stats.density).abs() < 1e-12);
+        assert_eq!(parsed.local_clustering.len(), stats.local_clustering.len());
+        assert!(parsed
+            .local_clustering
+            .iter()
+            .zip(&stats.local_clustering)
+            .all(|(x, y)| x.0 == y.0 && (x.1 - y.1).abs() < 1e-12));
+    }
+}
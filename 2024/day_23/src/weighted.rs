@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use crate::{cliques, Graph};
+
+/// Directed, weighted connections. Undirected connections are stored as a pair of opposite
+/// arcs. Clique algorithms run on [`ConnectionGraph::graph`], the symmetric part that only
+/// connects two nodes if there are arcs in both directions.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionGraph {
+    graph: Graph,
+    arcs: HashMap<(u32, u32), f64>,
+}
+
+/// Clique by sorted node names together with the sum of its edge weights
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedClique {
+    pub nodes: Vec<String>,
+    pub weight: f64,
+}
+
+impl ConnectionGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces the arc `from -> to`, self loops are ignored. Panics unless the weight is
+    /// finite and non-negative, which [`ConnectionGraph::maximum_weight_clique`] relies on.
+    pub fn add_arc(&mut self, from: &str, to: &str, weight: f64) {
+        assert!(
+            weight.is_finite() && weight >= 0.0,
+            "invalid weight {weight} for {from}->{to}"
+        );
+        let from = self.graph.intern(from);
+        let to = self.graph.intern(to);
+        if from == to {
+            return;
+        }
+        self.arcs.insert((from, to), weight);
+        if self.arcs.contains_key(&(to, from)) {
+            self.graph.add_edge_ids(from, to);
+        }
+    }
+
+    /// Adds or replaces the arcs in both directions
+    pub fn add_edge(&mut self, left: &str, right: &str, weight: f64) {
+        self.add_arc(left, right, weight);
+        self.add_arc(right, left, weight);
+    }
+
+    /// Symmetric part, containing every node that appears in any connection
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    pub fn num_arcs(&self) -> usize {
+        self.arcs.len()
+    }
+
+    pub fn arc_weight(&self, from: &str, to: &str) -> Option<f64> {
+        let key = (self.graph.id(from)?, self.graph.id(to)?);
+        self.arcs.get(&key).copied()
+    }
+
+    /// Mean weight of both directions, `None` if the nodes are not connected both ways
+    pub fn edge_weight(&self, left: u32, right: u32) -> Option<f64> {
+        let forward = self.arcs.get(&(left, right))?;
+        let backward = self.arcs.get(&(right, left))?;
+        Some((forward + backward) / 2.0)
+    }
+
+    fn clique_weight(&self, clique: &[u32]) -> f64 {
+        clique
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, left)| {
+                clique[idx + 1..]
+                    .iter()
+                    .map(|right| self.edge_weight(*left, *right).unwrap())
+            })
+            .sum()
+    }
+
+    fn heaviest(&self, cliques: impl IntoIterator<Item = Vec<u32>>) -> Option<WeightedClique> {
+        cliques
+            .into_iter()
+            .map(|clique| {
+                let mut nodes: Vec<String> = clique
+                    .iter()
+                    .map(|x| self.graph.name(*x).to_string())
+                    .collect();
+                nodes.sort();
+                WeightedClique {
+                    weight: self.clique_weight(&clique),
+                    nodes,
+                }
+            })
+            .min_by(|a, b| {
+                b.weight
+                    .total_cmp(&a.weight)
+                    .then_with(|| a.nodes.cmp(&b.nodes))
+            })
+    }
+
+    /// Clique with the largest sum of edge weights, ties broken by the lexicographically
+    /// smallest names. Weights must not be negative: then adding a node never makes a clique
+    /// lighter, so it suffices to compare the maximal cliques.
+    pub fn maximum_weight_clique(&self) -> Option<WeightedClique> {
+        self.heaviest(cliques::MaximalCliques::new(&self.graph))
+    }
+
+    /// Triangle with the largest sum of edge weights, ties broken by names
+    pub fn heaviest_triangle(&self) -> Option<WeightedClique> {
+        self.heaviest(cliques::k_cliques(&self.graph, 3))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ConnectionGraph, WeightedClique};
+    use crate::formats::parse_connections;
+
+    const CONNECTIONS: &str = "a-b:1\nb-c:2\na-c:3\nc->d:5\nd->c:7\nb->d\nx-y:10\n";
+
+    #[test]
+    fn test_symmetric_part() {
+        let connections = parse_connections(CONNECTIONS).unwrap();
+        let graph = connections.graph();
+        assert_eq!(graph.num_nodes(), 6);
+        assert_eq!(graph.num_edges(), 5);
+        assert_eq!(connections.num_arcs(), 11);
+
+        let (b, c, d) = (
+            graph.id("b").unwrap(),
+            graph.id("c").unwrap(),
+            graph.id("d").unwrap(),
+        );
+        assert!(!graph.has_edge(b, d));
+        assert_eq!(connections.arc_weight("b", "d"), Some(1.0));
+        assert_eq!(connections.edge_weight(c, d), Some(6.0));
+    }
+
+    #[test]
+    fn test_weighted_queries() {
+        let connections = parse_connections(CONNECTIONS).unwrap();
+        assert_eq!(
+            connections.heaviest_triangle(),
+            Some(WeightedClique {
+                nodes: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                weight: 6.0
+            })
+        );
+        assert_eq!(
+            connections.maximum_weight_clique().unwrap().nodes,
+            vec!["x", "y"]
+        );
+
+        let mut connections = ConnectionGraph::new();
+        connections.add_edge("p", "q", 1.0);
+        assert_eq!(connections.heaviest_triangle(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid weight")]
+    fn test_negative_weight() {
+        ConnectionGraph::new().add_edge("a", "b", -5.0);
+    }
+}
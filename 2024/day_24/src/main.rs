@@ -1,6 +1,12 @@
+mod polynomial;
+
+use itertools::Itertools;
+use polynomial::Polynomial;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env;
 use std::fmt;
 use std::fs::{read_to_string, write};
 use std::rc::Rc;
@@ -14,16 +20,83 @@ impl fmt::Display for CircularConnectionError {
     }
 }
 
+/// Three-valued logic level of a wire, `X` marks an unknown or undriven value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Signal {
+    #[serde(rename = "0")]
+    Zero,
+    #[serde(rename = "1")]
+    One,
+    X,
+}
+
+impl Signal {
+    fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Signal::Zero, _) | (_, Signal::Zero) => Signal::Zero,
+            (Signal::One, Signal::One) => Signal::One,
+            _ => Signal::X,
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Signal::One, _) | (_, Signal::One) => Signal::One,
+            (Signal::Zero, Signal::Zero) => Signal::Zero,
+            _ => Signal::X,
+        }
+    }
+
+    fn xor(self, other: Self) -> Self {
+        match (self, other) {
+            (Signal::X, _) | (_, Signal::X) => Signal::X,
+            (a, b) => Signal::from(a != b),
+        }
+    }
+}
+
+impl From<bool> for Signal {
+    fn from(value: bool) -> Self {
+        if value {
+            Signal::One
+        } else {
+            Signal::Zero
+        }
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Signal::Zero => write!(f, "0"),
+            Signal::One => write!(f, "1"),
+            Signal::X => write!(f, "X"),
+        }
+    }
+}
+
+/// Binary mode rejects undriven wires, ternary mode treats them as inputs with value `X`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SimulationMode {
+    Binary,
+    Ternary,
+}
+
+type Variables = HashMap<String, Rc<RefCell<LogicVariable>>>;
+type Gates = HashMap<String, Rc<RefCell<LogicGate>>>;
+
 struct LogicVariable {
-    value: Option<bool>,
+    value: Option<Signal>,
     user_gates: Vec<(Rc<RefCell<LogicGate>>, usize)>,
+    /// Read by a gate but neither declared as input nor driven by a gate
+    floating: bool,
 }
 
 struct LogicGate {
-    input1: Option<bool>,
-    input2: Option<bool>,
+    input1: Option<Signal>,
+    input2: Option<Signal>,
     operation_name: String,
-    operation: Box<dyn Fn(bool, bool) -> bool>,
+    operation: Box<dyn Fn(Signal, Signal) -> Signal>,
     output: Rc<RefCell<LogicVariable>>,
 }
 
@@ -32,17 +105,31 @@ impl LogicVariable {
         Rc::new(RefCell::new(Self {
             value: None,
             user_gates: Vec::new(),
+            floating: false,
         }))
     }
 
-    pub fn new_with_value(value: bool) -> Rc<RefCell<Self>> {
+    pub fn new_with_value(value: Signal) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Self {
             value: Some(value),
             user_gates: Vec::new(),
+            floating: false,
+        }))
+    }
+
+    pub fn new_floating() -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            value: Some(Signal::X),
+            user_gates: Vec::new(),
+            floating: true,
         }))
     }
 
-    pub fn set_value(&mut self, value: bool) -> Result<(), CircularConnectionError> {
+    pub fn set_value(&mut self, value: Signal) -> Result<(), CircularConnectionError> {
+        // Only the fan-out cone of a wire whose value actually changed needs re-evaluation
+        if self.value == Some(value) {
+            return Ok(());
+        }
         self.value = Some(value);
         self.propagate()
     }
@@ -54,7 +141,7 @@ impl LogicVariable {
     fn propagate(&mut self) -> Result<(), CircularConnectionError> {
         for (gate, input_index) in &mut self.user_gates {
             match gate.try_borrow_mut() {
-                Ok(mut x) => x.set_input(*input_index, self.value.unwrap_or(false))?,
+                Ok(mut x) => x.set_input(*input_index, self.value.unwrap_or(Signal::X))?,
                 Err(_) => return Err(CircularConnectionError {}),
             }
         }
@@ -69,7 +156,7 @@ impl LogicGate {
         variable: Rc<RefCell<LogicVariable>>,
     ) -> Rc<RefCell<Self>>
     where
-        F: Fn(bool, bool) -> bool + 'static,
+        F: Fn(Signal, Signal) -> Signal + 'static,
     {
         Rc::new(RefCell::new(Self {
             input1: None,
@@ -81,18 +168,22 @@ impl LogicGate {
     }
 
     pub fn new_and(variable: Rc<RefCell<LogicVariable>>) -> Rc<RefCell<Self>> {
-        Self::new("AND".to_string(), |a, b| a && b, variable)
+        Self::new("AND".to_string(), Signal::and, variable)
     }
 
     pub fn new_or(variable: Rc<RefCell<LogicVariable>>) -> Rc<RefCell<Self>> {
-        Self::new("OR".to_string(), |a, b| a || b, variable)
+        Self::new("OR".to_string(), Signal::or, variable)
     }
 
     pub fn new_xor(variable: Rc<RefCell<LogicVariable>>) -> Rc<RefCell<Self>> {
-        Self::new("XOR".to_string(), |a, b| a ^ b, variable)
+        Self::new("XOR".to_string(), Signal::xor, variable)
     }
 
-    pub fn set_input(&mut self, index: usize, value: bool) -> Result<(), CircularConnectionError> {
+    pub fn set_input(
+        &mut self,
+        index: usize,
+        value: Signal,
+    ) -> Result<(), CircularConnectionError> {
         match index {
             0 => self.input1 = Some(value),
             1 => self.input2 = Some(value),
@@ -103,102 +194,603 @@ impl LogicGate {
 
     fn activate(&mut self) -> Result<(), CircularConnectionError> {
         if let (Some(a), Some(b)) = (self.input1, self.input2) {
-            self.output.borrow_mut().set_value((self.operation)(a, b))?;
+            match self.output.try_borrow_mut() {
+                Ok(mut x) => x.set_value((self.operation)(a, b))?,
+                Err(_) => return Err(CircularConnectionError {}),
+            }
         }
         Ok(())
     }
+
+    fn reset(&mut self) {
+        self.input1 = None;
+        self.input2 = None;
+    }
+}
+
+fn parse_file(filename: &str) -> (Variables, Gates, HashSet<String>) {
+    parse_file_with_mode(filename, SimulationMode::Binary)
+}
+
+/// Plain description of a circuit, used as the interchange format for JSON import and export
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct CircuitDescription {
+    initial_values: BTreeMap<String, Signal>,
+    gates: Vec<GateDescription>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct GateDescription {
+    output: String,
+    operation: String,
+    input1: String,
+    input2: String,
+}
+
+/// Values of all wires after a simulation run together with the decoded integers
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct SimulationReport {
+    wires: BTreeMap<String, Option<Signal>>,
+    x: u64,
+    y: u64,
+    z: u64,
+    check_passed: Option<bool>,
 }
 
-fn parse_file(
+fn parse_file_with_mode(
     filename: &str,
-) -> (
-    HashMap<String, Rc<RefCell<LogicVariable>>>,
-    HashMap<String, Rc<RefCell<LogicGate>>>,
-    HashSet<String>,
-) {
-    let mut input_variables: HashSet<String> = HashSet::new();
-    let mut variables: HashMap<String, Rc<RefCell<LogicVariable>>> = HashMap::new();
-    let mut gates: HashMap<String, Rc<RefCell<LogicGate>>> = HashMap::new();
-    let mut connections: Vec<(String, String, String)> = Vec::new();
+    mode: SimulationMode,
+) -> (Variables, Gates, HashSet<String>) {
+    let content = read_to_string(filename).expect("Failed to read file");
+    let description = if filename.ends_with(".json") {
+        serde_json::from_str(&content).expect("Invalid circuit JSON")
+    } else {
+        parse_description(&content)
+    };
+
+    build_circuit(&description, mode)
+}
 
-    for line in read_to_string(filename)
-        .expect("Failed to read file")
-        .lines()
-    {
+fn parse_description(content: &str) -> CircuitDescription {
+    let mut description = CircuitDescription::default();
+
+    for line in content.lines() {
         if line.contains(":") {
             let parts = line.split_once(":").unwrap();
             let name = parts.0.trim().to_string();
-            let value = parts.1.trim().parse::<u32>().unwrap() != 0;
-            let input_var = LogicVariable::new_with_value(value);
-            variables.insert(name.clone(), input_var.clone());
-            input_variables.insert(name);
+            let value = match parts.1.trim() {
+                "X" | "x" => Signal::X,
+                value => Signal::from(value.parse::<u32>().unwrap() != 0),
+            };
+            description.initial_values.insert(name, value);
         } else if line.contains("->") {
             let parts = line.split_once("->").unwrap();
-            let name = parts.1.trim().to_string();
-
             let gate_parts: Vec<&str> = parts.0.split_whitespace().collect();
-            let input1_name = gate_parts[0].to_string();
-            let operation = gate_parts[1];
-            let input2_name = gate_parts[2].to_string();
-
-            let output_var = LogicVariable::new();
-            variables.insert(name.clone(), output_var.clone());
-
-            let gate = match operation {
-                "AND" => LogicGate::new_and(output_var.clone()),
-                "OR" => LogicGate::new_or(output_var.clone()),
-                "XOR" => LogicGate::new_xor(output_var.clone()),
-                _ => panic!("Unknown operation: {}", operation),
-            };
 
-            gates.insert(name.clone(), gate);
-            connections.push((name, input1_name, input2_name));
+            description.gates.push(GateDescription {
+                output: parts.1.trim().to_string(),
+                operation: gate_parts[1].to_string(),
+                input1: gate_parts[0].to_string(),
+                input2: gate_parts[2].to_string(),
+            });
         }
     }
 
-    for (gate_name, input1_name, input2_name) in connections {
-        let gate = gates.get(&gate_name).unwrap();
+    description
+}
 
-        if let Some(input1) = variables.get_mut(&input1_name) {
-            input1.borrow_mut().add_successor((gate.clone(), 0));
-        } else {
-            panic!("Unknown input: {}", input1_name);
+fn build_circuit(
+    description: &CircuitDescription,
+    mode: SimulationMode,
+) -> (Variables, Gates, HashSet<String>) {
+    let mut input_variables: HashSet<String> = HashSet::new();
+    let mut variables: Variables = HashMap::new();
+    let mut gates: Gates = HashMap::new();
+
+    for (name, value) in &description.initial_values {
+        if mode == SimulationMode::Binary && *value == Signal::X {
+            panic!("Unknown value for input: {}", name);
         }
+        variables.insert(name.clone(), LogicVariable::new_with_value(*value));
+        input_variables.insert(name.clone());
+    }
 
-        if let Some(input2) = variables.get_mut(&input2_name) {
-            input2.borrow_mut().add_successor((gate.clone(), 1));
-        } else {
-            panic!("Unknown input: {}", input2_name);
+    for gate_description in &description.gates {
+        let output_var = LogicVariable::new();
+        variables.insert(gate_description.output.clone(), output_var.clone());
+
+        let gate = match gate_description.operation.as_str() {
+            "AND" => LogicGate::new_and(output_var.clone()),
+            "OR" => LogicGate::new_or(output_var.clone()),
+            "XOR" => LogicGate::new_xor(output_var.clone()),
+            operation => panic!("Unknown operation: {}", operation),
+        };
+        gates.insert(gate_description.output.clone(), gate);
+    }
+
+    for gate_description in &description.gates {
+        let gate = gates.get(&gate_description.output).unwrap();
+
+        for (input_index, name) in [&gate_description.input1, &gate_description.input2]
+            .into_iter()
+            .enumerate()
+        {
+            if mode == SimulationMode::Ternary && !variables.contains_key(name) {
+                variables.insert(name.clone(), LogicVariable::new_floating());
+                input_variables.insert(name.clone());
+            }
+
+            if let Some(input) = variables.get_mut(name) {
+                input
+                    .borrow_mut()
+                    .add_successor((gate.clone(), input_index));
+            } else {
+                panic!("Unknown input: {}", name);
+            }
         }
     }
 
     (variables, gates, input_variables)
 }
 
+fn describe_circuit(
+    variables: &Variables,
+    gates: &Gates,
+    input_variables: &HashSet<String>,
+) -> CircuitDescription {
+    let fan_in = fan_in_map(variables);
+
+    // Floating wires stay undeclared so that they are floating again after an import
+    let initial_values = input_variables
+        .iter()
+        .filter(|name| !variables.get(*name).unwrap().borrow().floating)
+        .map(|name| {
+            let value = variables.get(name).unwrap().borrow().value;
+            (name.clone(), value.unwrap_or(Signal::X))
+        })
+        .collect();
+
+    let mut gate_descriptions: Vec<GateDescription> = gates
+        .iter()
+        .map(|(name, gate)| {
+            let [input1, input2] = fan_in.get(name).cloned().unwrap_or_default();
+            GateDescription {
+                output: name.clone(),
+                operation: gate.borrow().operation_name.clone(),
+                input1,
+                input2,
+            }
+        })
+        .collect();
+    gate_descriptions.sort_by(|a, b| a.output.cmp(&b.output));
+
+    CircuitDescription {
+        initial_values,
+        gates: gate_descriptions,
+    }
+}
+
+fn simulation_report(variables: &Variables, check_passed: Option<bool>) -> SimulationReport {
+    SimulationReport {
+        wires: variables
+            .iter()
+            .map(|(name, var)| (name.clone(), var.borrow().value))
+            .collect(),
+        x: get_value(variables, "x"),
+        y: get_value(variables, "y"),
+        z: get_output_value(variables),
+        check_passed,
+    }
+}
+
+fn variable_names(variables: &Variables) -> HashMap<*mut LogicVariable, String> {
+    variables
+        .iter()
+        .map(|(name, var)| (var.as_ptr(), name.clone()))
+        .collect()
+}
+
+/// Maps every gate output wire to the two wires feeding its gate
+fn fan_in_map(variables: &Variables) -> HashMap<String, [String; 2]> {
+    let var_to_name_map = variable_names(variables);
+    let mut result: HashMap<String, [String; 2]> = HashMap::new();
+
+    for (name, var) in variables {
+        for (gate, input_index) in &var.borrow().user_gates {
+            result
+                .entry(
+                    var_to_name_map
+                        .get(&gate.borrow().output.as_ptr())
+                        .unwrap()
+                        .clone(),
+                )
+                .or_default()[*input_index] = name.clone();
+        }
+    }
+
+    result
+}
+
+/// Transitive fan-in of a single wire, gates are named by their output wire
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ConeOfInfluence {
+    inputs: BTreeSet<String>,
+    gates: BTreeSet<String>,
+}
+
+impl ConeOfInfluence {
+    fn contains(&self, name: &str) -> bool {
+        self.inputs.contains(name) || self.gates.contains(name)
+    }
+}
+
+/// `fan_in` is the result of [`fan_in_map`], so that it is built once for several cones
+fn cone_of_influence(
+    fan_in: &HashMap<String, [String; 2]>,
+    input_variables: &HashSet<String>,
+    output: &str,
+) -> ConeOfInfluence {
+    let mut cone = ConeOfInfluence::default();
+    let mut stack = vec![output.to_string()];
+
+    while let Some(name) = stack.pop() {
+        if cone.contains(&name) {
+            continue;
+        }
+        if input_variables.contains(&name) {
+            cone.inputs.insert(name);
+        } else {
+            if let Some(inputs) = fan_in.get(&name) {
+                stack.extend(inputs.iter().cloned());
+            }
+            cone.gates.insert(name);
+        }
+    }
+
+    cone
+}
+
+fn output_cones(
+    variables: &Variables,
+    input_variables: &HashSet<String>,
+) -> Vec<(String, ConeOfInfluence)> {
+    let fan_in = fan_in_map(variables);
+    let mut result = Vec::new();
+    let mut idx = 0;
+    while variables.contains_key(&format!("z{:02}", idx)) {
+        let name = format!("z{:02}", idx);
+        let cone = cone_of_influence(&fan_in, input_variables, &name);
+        result.push((name, cone));
+        idx += 1;
+    }
+
+    result
+}
+
+/// For a ripple-carry adder `zNN` must depend on exactly `x00..xNN` and `y00..yNN`
+fn expected_adder_inputs(output: &str, input_variables: &HashSet<String>) -> BTreeSet<String> {
+    let pos = output[1..].parse::<u32>().unwrap();
+    (0..=pos)
+        .flat_map(|idx| [format!("x{:02}", idx), format!("y{:02}", idx)])
+        .filter(|name| input_variables.contains(name))
+        .collect()
+}
+
+fn unexpected_dependencies(
+    variables: &Variables,
+    input_variables: &HashSet<String>,
+) -> Vec<String> {
+    output_cones(variables, input_variables)
+        .into_iter()
+        .filter(|(name, cone)| cone.inputs != expected_adder_inputs(name, input_variables))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+fn dependency_table(variables: &Variables, input_variables: &HashSet<String>) -> String {
+    let mut out_str = format!(
+        "{:<6} {:>6} {:>6}  {}\n",
+        "output", "inputs", "gates", "status"
+    );
+
+    for (name, cone) in output_cones(variables, input_variables) {
+        let expected = expected_adder_inputs(&name, input_variables);
+        let missing: Vec<&String> = expected.difference(&cone.inputs).collect();
+        let extra: Vec<&String> = cone.inputs.difference(&expected).collect();
+
+        let mut status = Vec::new();
+        if !missing.is_empty() {
+            status.push(format!("missing {}", missing.iter().join(",")));
+        }
+        if !extra.is_empty() {
+            status.push(format!("extra {}", extra.iter().join(",")));
+        }
+        if status.is_empty() {
+            status.push("ok".to_string());
+        }
+
+        out_str.push_str(
+            format!(
+                "{:<6} {:>6} {:>6}  {}\n",
+                name,
+                cone.inputs.len(),
+                cone.gates.len(),
+                status.join("; ")
+            )
+            .as_str(),
+        );
+    }
+
+    out_str
+}
+
+/// Arithmetic relation between the `x`/`y` inputs and the `z` outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArithmeticSpec {
+    Sum,
+    Product,
+}
+
+/// Gates ordered such that every gate comes after the gates driving its inputs
+fn topological_gate_order(
+    variables: &Variables,
+    input_variables: &HashSet<String>,
+) -> Result<Vec<String>, CircularConnectionError> {
+    let fan_in = fan_in_map(variables);
+    let mut order = Vec::new();
+    let mut finished: HashSet<String> = HashSet::new();
+    let mut in_progress: HashSet<String> = HashSet::new();
+
+    let mut roots: Vec<&String> = fan_in.keys().collect();
+    roots.sort();
+    for root in roots {
+        let mut stack = vec![(root.clone(), false)];
+        while let Some((name, expanded)) = stack.pop() {
+            if expanded {
+                in_progress.remove(&name);
+                finished.insert(name.clone());
+                order.push(name);
+                continue;
+            }
+            if finished.contains(&name) || input_variables.contains(&name) {
+                continue;
+            }
+            if !in_progress.insert(name.clone()) {
+                return Err(CircularConnectionError {});
+            }
+            stack.push((name.clone(), true));
+            for input in fan_in.get(&name).into_iter().flatten() {
+                if in_progress.contains(input) {
+                    return Err(CircularConnectionError {});
+                }
+                stack.push((input.clone(), false));
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// Faulty circuits can make the remainder grow exponentially, so rewriting gives up beyond this size
+const MAX_REWRITE_TERMS: usize = 10_000;
+
+/// Polynomial coefficients are `i128`, wider words would overflow the bit weights. For a
+/// product this bounds the width of `x * y`, i.e. the sum of both widths minus one.
+const MAX_WORD_BITS: usize = 126;
+
+/// Outcome of [`verify_arithmetic`]
+#[derive(Debug, PartialEq, Eq)]
+enum Verification {
+    Verified,
+    /// The circuit is wrong, the remainder is non-zero for some inputs
+    Refuted {
+        remainder: String,
+    },
+    /// Rewriting gave up before reaching a result
+    Inconclusive(String),
+}
+
+#[derive(Debug)]
+enum VerificationError {
+    Circular(CircularConnectionError),
+    WordTooWide { word: String, bits: usize },
+}
+
+impl From<CircularConnectionError> for VerificationError {
+    fn from(err: CircularConnectionError) -> Self {
+        VerificationError::Circular(err)
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerificationError::Circular(err) => write!(f, "{}", err),
+            VerificationError::WordTooWide { word, bits } => write!(
+                f,
+                "{} has {} bits, at most {} are supported",
+                word, bits, MAX_WORD_BITS
+            ),
+        }
+    }
+}
+
+/// Proves `z = x + y` or `z = x * y` by rewriting the specification polynomial backwards through
+/// all gates. What remains is a polynomial over the inputs which vanishes iff the circuit is correct.
+fn verify_arithmetic(
+    variables: &Variables,
+    gates: &Gates,
+    input_variables: &HashSet<String>,
+    spec: ArithmeticSpec,
+) -> Result<Verification, VerificationError> {
+    let order = topological_gate_order(variables, input_variables)?;
+    let fan_in = fan_in_map(variables);
+
+    let mut names: Vec<String> = variables.keys().cloned().collect();
+    names.sort();
+    let index: HashMap<&String, usize> = names.iter().enumerate().map(|(i, n)| (n, i)).collect();
+
+    let bit_name = |prefix: &str, idx: usize| format!("{}{:02}", prefix, idx);
+    let width = |prefix: &str| {
+        (0..)
+            .take_while(|idx| index.contains_key(&bit_name(prefix, *idx)))
+            .count()
+    };
+    let check_width = |word: &str, bits: usize| {
+        if bits > MAX_WORD_BITS {
+            return Err(VerificationError::WordTooWide {
+                word: word.to_string(),
+                bits,
+            });
+        }
+        Ok(())
+    };
+    let (x_bits, y_bits, z_bits) = (width("x"), width("y"), width("z"));
+    check_width("x", x_bits)?;
+    check_width("y", y_bits)?;
+    check_width("z", z_bits)?;
+    if spec == ArithmeticSpec::Product && x_bits > 0 && y_bits > 0 {
+        check_width("x * y", x_bits + y_bits - 1)?;
+    }
+
+    let word = |prefix: &str, bits: usize| {
+        let mut result = Polynomial::zero();
+        for idx in 0..bits {
+            let pos = *index.get(&bit_name(prefix, idx)).unwrap();
+            result = &result + &Polynomial::variable(pos).scale(1 << idx);
+        }
+        result
+    };
+    let (x, y) = (word("x", x_bits), word("y", y_bits));
+    let target = match spec {
+        ArithmeticSpec::Sum => &x + &y,
+        ArithmeticSpec::Product => (&x * &y).expect("Product width was checked"),
+    };
+    let mut remainder = &word("z", z_bits) - &target;
+
+    // An XOR and an AND gate sharing both inputs form a half adder, whose outputs are never
+    // both 1. Dropping their products right away avoids a blow-up of intermediate polynomials.
+    let mut half_adders: HashMap<[&String; 2], (Vec<usize>, Vec<usize>)> = HashMap::new();
+    for name in &order {
+        let mut inputs = fan_in.get(name).unwrap().each_ref();
+        inputs.sort();
+        let entry = half_adders.entry(inputs).or_default();
+        match gates.get(name).unwrap().borrow().operation_name.as_str() {
+            "XOR" => entry.0.push(*index.get(name).unwrap()),
+            "AND" => entry.1.push(*index.get(name).unwrap()),
+            _ => {}
+        }
+    }
+    let mut vanishing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (xor_outputs, and_outputs) in half_adders.values() {
+        for (a, b) in xor_outputs.iter().cartesian_product(and_outputs) {
+            vanishing.entry(*a).or_default().push(*b);
+            vanishing.entry(*b).or_default().push(*a);
+        }
+    }
+
+    // Rewriting gates with the longest path from the inputs first keeps the carry chains of
+    // adders in generate/propagate form until the very end
+    let mut levels: HashMap<&String, usize> = HashMap::new();
+    for name in &order {
+        let level = fan_in
+            .get(name)
+            .unwrap()
+            .iter()
+            .map(|input| levels.get(input).copied().unwrap_or(0))
+            .max()
+            .unwrap()
+            + 1;
+        levels.insert(name, level);
+    }
+    let mut rewrite_order: Vec<&String> = order.iter().collect();
+    rewrite_order.sort_by_key(|name| std::cmp::Reverse(levels.get(name).unwrap()));
+
+    for name in rewrite_order {
+        let [input1, input2] = fan_in.get(name).unwrap();
+        let a = Polynomial::variable(*index.get(input1).unwrap());
+        let b = Polynomial::variable(*index.get(input2).unwrap());
+        let product = (&a * &b).expect("Product of two variables");
+        let replacement = match gates.get(name).unwrap().borrow().operation_name.as_str() {
+            "AND" => product,
+            "OR" => &(&a + &b) - &product,
+            "XOR" => &(&a + &b) - &product.scale(2),
+            operation => panic!("Unknown operation: {}", operation),
+        };
+        remainder = match remainder.substitute(*index.get(name).unwrap(), &replacement) {
+            Ok(remainder) => remainder,
+            Err(err) => {
+                return Ok(Verification::Inconclusive(format!(
+                    "{} after rewriting {}",
+                    err, name
+                )))
+            }
+        };
+        if remainder.num_terms() > MAX_REWRITE_TERMS {
+            return Ok(Verification::Inconclusive(format!(
+                "more than {} terms after rewriting {}",
+                MAX_REWRITE_TERMS, name
+            )));
+        }
+
+        for input in [input1, input2] {
+            let input = *index.get(input).unwrap();
+            for other in vanishing.get(&input).into_iter().flatten() {
+                remainder.remove_vanishing(input, *other);
+            }
+        }
+    }
+
+    if remainder.is_zero() {
+        Ok(Verification::Verified)
+    } else {
+        Ok(Verification::Refuted {
+            remainder: remainder.format_with_names(&names),
+        })
+    }
+}
+
 fn dump_dot(
-    variables: &HashMap<String, Rc<RefCell<LogicVariable>>>,
-    gates: &HashMap<String, Rc<RefCell<LogicGate>>>,
+    variables: &Variables,
+    gates: &Gates,
     input_variables: &HashSet<String>,
+    highlight: Option<&ConeOfInfluence>,
 ) {
+    write(
+        "graph.dot",
+        dot_string(variables, gates, input_variables, highlight),
+    )
+    .unwrap();
+}
+
+fn dot_string(
+    variables: &Variables,
+    gates: &Gates,
+    input_variables: &HashSet<String>,
+    highlight: Option<&ConeOfInfluence>,
+) -> String {
     let mut out_str = "digraph G {\nlayout=neato;\n".to_string();
 
     let mut x_positions: HashMap<String, u32> = HashMap::new();
     let mut y_positions: HashMap<String, u32> = HashMap::new();
     let mut shapes: HashMap<String, String> = HashMap::new();
 
-    let mut var_to_name_map: HashMap<*mut LogicVariable, String> = HashMap::new();
-
-    for (name, var) in variables {
-        var_to_name_map.insert(var.as_ptr(), name.clone());
-    }
+    let var_to_name_map = variable_names(variables);
+    let is_highlighted = |name: &str| highlight.is_some_and(|cone| cone.contains(name));
 
     for (name, var) in variables {
         for (gate, _) in &var.borrow().user_gates {
+            let out_name = var_to_name_map.get(&gate.borrow().output.as_ptr()).unwrap();
             out_str.push_str(
                 format!(
-                    "{} -> {};\n",
+                    "{} -> {}{};\n",
                     name,
-                    var_to_name_map.get(&gate.borrow().output.as_ptr()).unwrap()
+                    out_name,
+                    if is_highlighted(name) && is_highlighted(out_name) {
+                        " [color=red]"
+                    } else {
+                        ""
+                    }
                 )
                 .as_str(),
             );
@@ -217,21 +809,32 @@ fn dump_dot(
         );
     }
 
+    // Floating wires in ternary mode can have any name, they get a row of their own
+    let bit_index = |name: &str, prefix: &str| {
+        name.strip_prefix(prefix)
+            .and_then(|pos| pos.parse::<u32>().ok())
+    };
+    let mut other_inputs: Vec<&String> = Vec::new();
     for name in input_variables {
-        let pos = name[1..].parse::<u32>().unwrap();
         shapes.insert(name.clone(), "circle".to_string());
-        if name.starts_with("x") {
+        if let Some(pos) = bit_index(name, "x") {
             x_positions.insert(name.to_string(), 2 * pos);
             y_positions.insert(name.to_string(), 0);
-        } else {
+        } else if let Some(pos) = bit_index(name, "y") {
             x_positions.insert(name.to_string(), 2 * pos + 1);
             y_positions.insert(name.to_string(), 1);
+        } else {
+            other_inputs.push(name);
         }
     }
+    other_inputs.sort();
+    for (idx, name) in other_inputs.into_iter().enumerate() {
+        x_positions.insert(name.to_string(), 2 * idx as u32);
+        y_positions.insert(name.to_string(), 7);
+    }
 
     for (name, gate) in gates {
-        if name.starts_with("z") {
-            let pos = name[1..].parse::<u32>().unwrap();
+        if let Some(pos) = bit_index(name, "z") {
             x_positions.insert(name.to_string(), 2 * pos);
             y_positions.insert(name.to_string(), 6);
         } else if gate.borrow().operation_name == "XOR" {
@@ -251,7 +854,7 @@ fn dump_dot(
     while !missing_gates.is_empty() {
         let mut new_x_positions = HashMap::new();
         for (key, pos) in &x_positions {
-            if key.starts_with("y") {
+            if input_variables.contains(key) && bit_index(key, "y").is_some() {
                 continue;
             }
             for (out_gate, _) in &variables.get(key).unwrap().borrow().user_gates {
@@ -269,38 +872,30 @@ fn dump_dot(
         x_positions.extend(new_x_positions);
     }
 
-    for name in input_variables {
-        out_str.push_str(
-            format!(
-                "{} [shape={}, pos=\"{},{}!\"];\n",
-                name,
-                shapes.get(name).unwrap(),
-                x_positions.get(name).unwrap(),
-                y_positions.get(name).unwrap()
-            )
-            .as_str(),
-        );
-    }
-
-    for name in gates.keys() {
+    for name in input_variables.iter().chain(gates.keys()) {
         out_str.push_str(
             format!(
-                "{} [shape={}, pos=\"{},{}!\"];\n",
+                "{} [shape={}, pos=\"{},{}!\"{}];\n",
                 name,
                 shapes.get(name).unwrap(),
                 x_positions.get(name).unwrap(),
-                y_positions.get(name).unwrap()
+                y_positions.get(name).unwrap(),
+                if is_highlighted(name) {
+                    ", color=red, style=bold"
+                } else {
+                    ""
+                }
             )
             .as_str(),
         );
     }
 
     out_str.push_str("}\n");
-    write("graph.dot", out_str).unwrap();
+    out_str
 }
 
 fn forward_input(
-    variables: &mut HashMap<String, Rc<RefCell<LogicVariable>>>,
+    variables: &mut Variables,
     input_variables: &HashSet<String>,
 ) -> Result<(), CircularConnectionError> {
     for var_name in input_variables {
@@ -309,53 +904,142 @@ fn forward_input(
     Ok(())
 }
 
-fn get_output_value(variables: &HashMap<String, Rc<RefCell<LogicVariable>>>) -> u64 {
+fn reset(variables: &Variables, gates: &Gates, input_variables: &HashSet<String>) {
+    for (name, var) in variables.iter() {
+        if !input_variables.contains(name) {
+            var.borrow_mut().value = None;
+        }
+    }
+    for gate in gates.values() {
+        gate.borrow_mut().reset();
+    }
+}
+
+fn get_value(variables: &Variables, prefix: &str) -> u64 {
     let mut value = 0;
     let mut idx = 0;
-    while let Some(var) = variables.get(&format!("z{:02}", idx)) {
-        value += (1 << idx) * var.borrow().value.unwrap_or(false) as u64;
+    while let Some(var) = variables.get(&format!("{}{:02}", prefix, idx)) {
+        value += (1 << idx) * (var.borrow().value == Some(Signal::One)) as u64;
         idx += 1;
     }
 
     value
 }
 
-fn target_sum(variables: &HashMap<String, Rc<RefCell<LogicVariable>>>) -> u64 {
-    let mut idx = 0;
+fn get_output_value(variables: &Variables) -> u64 {
+    get_value(variables, "z")
+}
 
-    let mut value_x = 0;
-    let mut value_y = 0;
+fn unknown_outputs(variables: &Variables) -> Vec<String> {
+    let mut result: Vec<String> = variables
+        .iter()
+        .filter(|(name, var)| {
+            name.starts_with("z") && var.borrow().value.unwrap_or(Signal::X) == Signal::X
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    result.sort();
 
-    while let Some(var) = variables.get(&format!("x{:02}", idx)) {
-        value_x += (1 << idx) * var.borrow().value.unwrap_or(false) as u64;
-        idx += 1;
-    }
+    result
+}
+
+/// Undriven wires that ternary mode added as inputs with value `X`
+fn floating_wires(variables: &Variables) -> Vec<String> {
+    let mut result: Vec<String> = variables
+        .iter()
+        .filter(|(_, var)| var.borrow().floating)
+        .map(|(name, _)| name.clone())
+        .collect();
+    result.sort();
+
+    result
+}
+
+/// Declared inputs whose value is explicitly given as `X`
+fn unknown_inputs(variables: &Variables, input_variables: &HashSet<String>) -> Vec<String> {
+    let mut result: Vec<String> = input_variables
+        .iter()
+        .filter(|name| {
+            let var = variables.get(*name).unwrap().borrow();
+            !var.floating && var.value == Some(Signal::X)
+        })
+        .cloned()
+        .collect();
+    result.sort();
+
+    result
+}
+
+fn target_sum(variables: &Variables) -> u64 {
+    get_value(variables, "x") + get_value(variables, "y")
+}
+
+/// Output bits compared against `x + y`, bits whose expected or actual value is `X` can not
+/// be judged and are only reported as unknown
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SumCheck {
+    wrong: Vec<String>,
+    unknown: Vec<String>,
+}
+
+/// Ripple-carry addition in three-valued logic, so an `X` input only makes the bits unknown
+/// that actually depend on it
+fn check_sum(variables: &Variables) -> SumCheck {
+    // Missing input bits are 0, wires without a value yet are unknown
+    let signal = |name: &str| {
+        variables
+            .get(name)
+            .map_or(Signal::Zero, |var| var.borrow().value.unwrap_or(Signal::X))
+    };
+
+    let mut result = SumCheck::default();
+    let mut carry = Signal::Zero;
+    let mut idx = 0;
+    let exists =
+        |prefix: &str, idx: usize| variables.contains_key(&format!("{}{:02}", prefix, idx));
+    // Output bits beyond the last `zNN` wire count as 0
+    while ["x", "y", "z"].iter().any(|x| exists(x, idx)) || carry != Signal::Zero {
+        let name = format!("z{:02}", idx);
+        let (x, y) = (
+            signal(&format!("x{:02}", idx)),
+            signal(&format!("y{:02}", idx)),
+        );
+        let expected = x.xor(y).xor(carry);
+        carry = x.and(y).or(carry.and(x.xor(y)));
 
-    idx = 0;
-    while let Some(var) = variables.get(&format!("y{:02}", idx)) {
-        value_y += (1 << idx) * var.borrow().value.unwrap_or(false) as u64;
+        let actual = if exists("z", idx) {
+            signal(&name)
+        } else {
+            Signal::Zero
+        };
+        if expected == Signal::X || actual == Signal::X {
+            result.unknown.push(name);
+        } else if expected != actual {
+            result.wrong.push(name);
+        }
         idx += 1;
     }
 
-    value_x + value_y
+    result
 }
 
 fn check_gates(
-    variables: &mut HashMap<String, Rc<RefCell<LogicVariable>>>,
+    variables: &mut Variables,
+    gates: &Gates,
     input_variables: &HashSet<String>,
     num_tests: u32,
 ) -> bool {
-    let initial_states: HashMap<String, Option<bool>> = input_variables
+    let initial_states: HashMap<String, Option<Signal>> = input_variables
         .iter()
-        .map(|x| (x.clone(), variables.get(x).unwrap().borrow().value.clone()))
+        .map(|x| (x.clone(), variables.get(x).unwrap().borrow().value))
         .collect();
+    reset(variables, gates, input_variables);
     forward_input(variables, input_variables).unwrap();
-    assert_eq!(target_sum(variables), get_output_value(variables));
+    assert!(check_sum(variables).wrong.is_empty());
 
     let mut result = true;
     for _ in 0..num_tests {
         assign_random_inputs(variables, input_variables);
-        forward_input(variables, input_variables).unwrap();
         if target_sum(variables) != get_output_value(variables) {
             result = false;
             break;
@@ -363,7 +1047,14 @@ fn check_gates(
     }
 
     for (k, v) in initial_states {
-        variables.get(&k).unwrap().borrow_mut().value = v;
+        if let Some(v) = v {
+            variables
+                .get(&k)
+                .unwrap()
+                .borrow_mut()
+                .set_value(v)
+                .unwrap();
+        }
     }
 
     result
@@ -372,8 +1063,8 @@ fn check_gates(
 fn switch_gate_outputs(
     name_1: &String,
     name_2: &String,
-    gates: &mut HashMap<String, Rc<RefCell<LogicGate>>>,
-) {
+    gates: &mut Gates,
+) -> Result<(), CircularConnectionError> {
     let gate_1 = gates.get(name_1).expect("Gate not found").clone();
     let gate_2 = gates.get(name_2).expect("Gate not found").clone();
 
@@ -385,6 +1076,11 @@ fn switch_gate_outputs(
 
     gates.insert(name_1.clone(), gate_2.clone());
     gates.insert(name_2.clone(), gate_1.clone());
+
+    // Re-evaluate both gates so that their new outputs and fan-out cones are not left stale
+    gate_1.borrow_mut().activate()?;
+    gate_2.borrow_mut().activate()?;
+    Ok(())
 }
 
 fn flatten_switches(switches: &Vec<(String, String)>) -> Vec<String> {
@@ -398,22 +1094,21 @@ fn flatten_switches(switches: &Vec<(String, String)>) -> Vec<String> {
     sorted_switches
 }
 
-fn assign_random_inputs(
-    variables: &mut HashMap<String, Rc<RefCell<LogicVariable>>>,
-    input_variables: &HashSet<String>,
-) {
+fn assign_random_inputs(variables: &mut Variables, input_variables: &HashSet<String>) {
     let mut rng = rand::thread_rng();
     for name in input_variables {
         variables
             .get(name)
             .unwrap()
             .borrow_mut()
-            .set_value(rng.gen_bool(0.5))
+            .set_value(rng.gen_bool(0.5).into())
             .unwrap();
     }
 }
 
+// `main` comes last, after the tests, like in the other days
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod test {
     use super::*;
 
@@ -446,11 +1141,55 @@ mod test {
     #[test]
     fn test_switch() {
         let (mut variables, mut gates, input_variables) = parse_file("testinput.txt");
-        switch_gate_outputs(&String::from("z01"), &String::from("z02"), &mut gates);
+        switch_gate_outputs(&String::from("z01"), &String::from("z02"), &mut gates).unwrap();
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(get_output_value(&variables), 2);
+    }
+
+    #[test]
+    fn test_switch_after_simulation() {
+        let (mut variables, mut gates, input_variables) = parse_file("testinput.txt");
         forward_input(&mut variables, &input_variables).unwrap();
+        switch_gate_outputs(&String::from("z01"), &String::from("z02"), &mut gates).unwrap();
         assert_eq!(get_output_value(&variables), 2);
     }
 
+    #[test]
+    fn test_incremental_input_change() {
+        let (mut variables, _, input_variables) = parse_file("testinput2.txt");
+        forward_input(&mut variables, &input_variables).unwrap();
+        for (name, value) in [("x00", true), ("y04", false), ("x00", false)] {
+            variables
+                .get(name)
+                .unwrap()
+                .borrow_mut()
+                .set_value(value.into())
+                .unwrap();
+        }
+        let incremental_value = get_output_value(&variables);
+
+        let (mut variables, _, input_variables) = parse_file("testinput2.txt");
+        variables.get("x00").unwrap().borrow_mut().value = Some(Signal::Zero);
+        variables.get("y04").unwrap().borrow_mut().value = Some(Signal::Zero);
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(incremental_value, get_output_value(&variables));
+    }
+
+    #[test]
+    fn test_reset() {
+        let (mut variables, gates, input_variables) = parse_file("testinput.txt");
+        forward_input(&mut variables, &input_variables).unwrap();
+        reset(&variables, &gates, &input_variables);
+        assert!(variables.get("z00").unwrap().borrow().value.is_none());
+        assert_eq!(
+            variables.get("x00").unwrap().borrow().value,
+            Some(Signal::One)
+        );
+
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(get_output_value(&variables), 4);
+    }
+
     #[test]
     fn test_flatten() {
         let switches = vec![
@@ -459,18 +1198,329 @@ mod test {
         ];
         assert_eq!(
             flatten_switches(&switches),
-            vec!["a", "b", "c", "d"]
+            ["a", "b", "c", "d"]
                 .iter()
                 .map(|x| x.to_string())
                 .collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn test_ternary_operations() {
+        assert_eq!(Signal::Zero.and(Signal::X), Signal::Zero);
+        assert_eq!(Signal::One.and(Signal::X), Signal::X);
+        assert_eq!(Signal::One.or(Signal::X), Signal::One);
+        assert_eq!(Signal::Zero.or(Signal::X), Signal::X);
+        assert_eq!(Signal::One.xor(Signal::X), Signal::X);
+        assert_eq!(Signal::One.xor(Signal::Zero), Signal::One);
+    }
+
+    #[test]
+    fn test_floating_wires() {
+        let (variables, gates, input_variables) =
+            parse_file_with_mode("testinput4.txt", SimulationMode::Ternary);
+        assert_eq!(floating_wires(&variables), vec!["x02"]);
+        assert_eq!(unknown_inputs(&variables, &input_variables), vec!["y00"]);
+
+        let description = describe_circuit(&variables, &gates, &input_variables);
+        assert!(!description.initial_values.contains_key("x02"));
+        assert_eq!(description.initial_values.get("y00"), Some(&Signal::X));
+    }
+
+    #[test]
+    fn test_unknown_outputs() {
+        let (mut variables, _, input_variables) =
+            parse_file_with_mode("testinput4.txt", SimulationMode::Ternary);
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(unknown_outputs(&variables), vec!["z00", "z02"]);
+        assert_eq!(
+            variables.get("z01").unwrap().borrow().value,
+            Some(Signal::Zero)
+        );
+
+        variables
+            .get("y02")
+            .unwrap()
+            .borrow_mut()
+            .set_value(Signal::One)
+            .unwrap();
+        assert_eq!(unknown_outputs(&variables), vec!["z00"]);
+    }
+
+    #[test]
+    fn test_check_sum_ternary() {
+        let (mut variables, mut gates, input_variables) =
+            parse_file_with_mode("input.txt", SimulationMode::Ternary);
+        for (left, right) in [
+            ("vcf", "z10"),
+            ("z17", "fhg"),
+            ("z39", "tnc"),
+            ("fsq", "dvb"),
+        ] {
+            switch_gate_outputs(&left.to_string(), &right.to_string(), &mut gates).unwrap();
+        }
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(check_sum(&variables), SumCheck::default());
+
+        variables
+            .get("x05")
+            .unwrap()
+            .borrow_mut()
+            .set_value(Signal::X)
+            .unwrap();
+        let sum_check = check_sum(&variables);
+        assert!(sum_check.wrong.is_empty());
+        assert!(sum_check.unknown.contains(&"z05".to_string()));
+        assert!(!sum_check.unknown.contains(&"z04".to_string()));
+        assert!(check_gates(&mut variables, &gates, &input_variables, 10));
+        assert_eq!(
+            variables.get("x05").unwrap().borrow().value,
+            Some(Signal::X)
+        );
+
+        // Swapping two outputs back is a fault on bits that are known
+        switch_gate_outputs(&"z39".to_string(), &"tnc".to_string(), &mut gates).unwrap();
+        variables
+            .get("x05")
+            .unwrap()
+            .borrow_mut()
+            .set_value(Signal::One)
+            .unwrap();
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert!(!check_sum(&variables).wrong.is_empty());
+    }
+
+    #[test]
+    fn test_dot_with_floating_wires() {
+        let (variables, gates, input_variables) =
+            parse_file_with_mode("testinput4.txt", SimulationMode::Ternary);
+        let mut description = describe_circuit(&variables, &gates, &input_variables);
+        description.gates[0].input2 = "carry".to_string();
+        let (variables, gates, input_variables) =
+            build_circuit(&description, SimulationMode::Ternary);
+        assert_eq!(floating_wires(&variables), vec!["carry", "x02"]);
+        let dot = dot_string(&variables, &gates, &input_variables, None);
+        assert!(dot.contains("carry [shape=circle, pos=\"0,7!\"];"));
+    }
+
+    #[test]
+    fn test_cone_of_influence() {
+        let (variables, _, input_variables) = parse_file("testinput2.txt");
+        let cone = cone_of_influence(&fan_in_map(&variables), &input_variables, "z00");
+        assert_eq!(
+            cone.inputs,
+            ["x00", "x01", "x03", "y02", "y04"]
+                .iter()
+                .map(|x| x.to_string())
+                .collect()
+        );
+        assert!(cone.gates.contains("z00"));
+    }
+
+    #[test]
+    fn test_unexpected_dependencies() {
+        let (variables, _, input_variables) = parse_file("testinput.txt");
+        assert_eq!(
+            unexpected_dependencies(&variables, &input_variables),
+            vec!["z01", "z02"]
+        );
+    }
+
+    #[test]
+    fn test_unexpected_dependencies_adder() {
+        let (variables, mut gates, input_variables) = parse_file("input.txt");
+        assert!(unexpected_dependencies(&variables, &input_variables).contains(&"z10".to_string()));
+
+        for (left, right) in [
+            ("vcf", "z10"),
+            ("z17", "fhg"),
+            ("z39", "tnc"),
+            ("fsq", "dvb"),
+        ] {
+            switch_gate_outputs(&left.to_string(), &right.to_string(), &mut gates).unwrap();
+        }
+        assert!(unexpected_dependencies(&variables, &input_variables).is_empty());
+    }
+
+    #[test]
+    fn test_json_import() {
+        let (mut variables, _, input_variables) = parse_file("testinput.json");
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(get_output_value(&variables), 4);
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let (variables, gates, input_variables) = parse_file("testinput2.txt");
+        let json = serde_json::to_string(&describe_circuit(&variables, &gates, &input_variables));
+        let description: CircuitDescription = serde_json::from_str(&json.unwrap()).unwrap();
+        assert_eq!(
+            description,
+            describe_circuit(&variables, &gates, &input_variables)
+        );
+
+        let (mut variables, _, input_variables) =
+            build_circuit(&description, SimulationMode::Binary);
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(get_output_value(&variables), 2024);
+    }
+
+    #[test]
+    fn test_simulation_report() {
+        let (mut variables, _, input_variables) = parse_file("testinput.txt");
+        forward_input(&mut variables, &input_variables).unwrap();
+        let report = simulation_report(&variables, None);
+        assert_eq!((report.x, report.y, report.z), (7, 2, 4));
+        assert_eq!(report.wires.get("z01"), Some(&Some(Signal::Zero)));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["wires"]["z02"], "1");
+        assert_eq!(json["check_passed"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_multiplier_simulation() {
+        let (mut variables, _, input_variables) = parse_file("testinput5.txt");
+        forward_input(&mut variables, &input_variables).unwrap();
+        assert_eq!(get_output_value(&variables), 11 * 13);
+    }
+
+    #[test]
+    fn test_verify_multiplier() {
+        let (variables, mut gates, input_variables) = parse_file("testinput5.txt");
+        let verify = |gates: &Gates, spec| {
+            verify_arithmetic(&variables, gates, &input_variables, spec).unwrap()
+        };
+        assert_eq!(
+            verify(&gates, ArithmeticSpec::Product),
+            Verification::Verified
+        );
+        assert!(matches!(
+            verify(&gates, ArithmeticSpec::Sum),
+            Verification::Refuted { .. }
+        ));
+
+        switch_gate_outputs(&String::from("z03"), &String::from("z04"), &mut gates).unwrap();
+        assert!(matches!(
+            verify(&gates, ArithmeticSpec::Product),
+            Verification::Refuted { .. }
+        ));
+    }
+
+    #[test]
+    fn test_verify_adder() {
+        let (variables, mut gates, input_variables) = parse_file("input.txt");
+        // The swapped outputs make the remainder blow up, which is not a disproof
+        assert!(matches!(
+            verify_arithmetic(&variables, &gates, &input_variables, ArithmeticSpec::Sum).unwrap(),
+            Verification::Inconclusive(_)
+        ));
+
+        for (left, right) in [
+            ("vcf", "z10"),
+            ("z17", "fhg"),
+            ("z39", "tnc"),
+            ("fsq", "dvb"),
+        ] {
+            switch_gate_outputs(&left.to_string(), &right.to_string(), &mut gates).unwrap();
+        }
+        assert_eq!(
+            verify_arithmetic(&variables, &gates, &input_variables, ArithmeticSpec::Sum).unwrap(),
+            Verification::Verified
+        );
+    }
+
+    #[test]
+    fn test_verify_circular() {
+        let (variables, mut gates, input_variables) = parse_file("testinput5.txt");
+        switch_gate_outputs(&String::from("w02"), &String::from("z07"), &mut gates).unwrap();
+        assert!(matches!(
+            verify_arithmetic(
+                &variables,
+                &gates,
+                &input_variables,
+                ArithmeticSpec::Product
+            ),
+            Err(VerificationError::Circular(_))
+        ));
+    }
+
+    /// `zNN = xNN XOR yNN` for `bits` bits
+    fn bitwise_xor(bits: usize) -> (Variables, Gates, HashSet<String>) {
+        let mut description = CircuitDescription::default();
+        for idx in 0..bits {
+            for prefix in ["x", "y"] {
+                description
+                    .initial_values
+                    .insert(format!("{}{:02}", prefix, idx), Signal::Zero);
+            }
+            description.gates.push(GateDescription {
+                output: format!("z{:02}", idx),
+                operation: "XOR".to_string(),
+                input1: format!("x{:02}", idx),
+                input2: format!("y{:02}", idx),
+            });
+        }
+        build_circuit(&description, SimulationMode::Binary)
+    }
+
+    #[test]
+    fn test_verify_word_too_wide() {
+        let verify = |bits: usize, spec| {
+            let (variables, gates, input_variables) = bitwise_xor(bits);
+            verify_arithmetic(&variables, &gates, &input_variables, spec)
+        };
+        assert!(matches!(
+            verify(MAX_WORD_BITS + 1, ArithmeticSpec::Sum),
+            Err(VerificationError::WordTooWide { bits: 127, .. })
+        ));
+        assert!(matches!(
+            verify(65, ArithmeticSpec::Product),
+            Err(VerificationError::WordTooWide { bits: 129, .. })
+        ));
+        assert!(matches!(
+            verify(64, ArithmeticSpec::Product),
+            Err(VerificationError::WordTooWide { bits: 127, .. })
+        ));
+        assert!(matches!(
+            verify(8, ArithmeticSpec::Product),
+            Ok(Verification::Refuted { .. })
+        ));
+        assert!(matches!(
+            verify(MAX_WORD_BITS, ArithmeticSpec::Sum),
+            Ok(Verification::Refuted { .. })
+        ));
+    }
 }
 
 fn main() {
-    let (mut variables, mut gates, input_variables) = parse_file("input.txt");
+    let ternary = env::args().any(|arg| arg == "--ternary");
+    let (mut variables, mut gates, input_variables) = if ternary {
+        parse_file_with_mode("input.txt", SimulationMode::Ternary)
+    } else {
+        parse_file("input.txt")
+    };
     forward_input(&mut variables, &input_variables).unwrap();
     println!("Challenge 1: {}", get_output_value(&variables));
+    if ternary {
+        println!("Floating wires: {}", floating_wires(&variables).join(","));
+        println!(
+            "Unknown inputs: {}",
+            unknown_inputs(&variables, &input_variables).join(",")
+        );
+        println!("Unknown outputs: {}", unknown_outputs(&variables).join(","));
+        let sum_check = check_sum(&variables);
+        println!("Wrong sum bits: {}", sum_check.wrong.join(","));
+        println!("Unknown sum bits: {}", sum_check.unknown.join(","));
+    }
+
+    if env::args().any(|arg| arg == "--dependencies") {
+        print!("{}", dependency_table(&variables, &input_variables));
+        println!(
+            "Unexpected dependencies: {}",
+            unexpected_dependencies(&variables, &input_variables).join(",")
+        );
+    }
 
     let switches = vec![
         ("vcf".to_string(), "z10".to_string()),
@@ -479,10 +1529,48 @@ fn main() {
         ("fsq".to_string(), "dvb".to_string()),
     ];
     for (left, right) in &switches {
-        switch_gate_outputs(left, right, &mut gates);
+        switch_gate_outputs(left, right, &mut gates).unwrap();
+    }
+    if let Some(spec) = env::args().skip_while(|arg| arg != "--verify").nth(1) {
+        let spec = match spec.as_str() {
+            "sum" => ArithmeticSpec::Sum,
+            "product" => ArithmeticSpec::Product,
+            _ => panic!("Unknown specification: {}", spec),
+        };
+        match verify_arithmetic(&variables, &gates, &input_variables, spec) {
+            Ok(Verification::Verified) => println!("Verified: {:?}", spec),
+            Ok(Verification::Refuted { remainder }) => {
+                println!("Verification failed, remainder: {}", remainder)
+            }
+            Ok(Verification::Inconclusive(reason)) => {
+                println!("Verification inconclusive: {}", reason)
+            }
+            Err(err) => println!("Verification not possible: {}", err),
+        }
     }
-    if check_gates(&mut variables, &input_variables, 100) {
-        dump_dot(&variables, &gates, &input_variables);
+
+    let check_passed = check_gates(&mut variables, &gates, &input_variables, 100);
+    if check_passed {
+        let highlight = env::args()
+            .skip_while(|arg| arg != "--highlight")
+            .nth(1)
+            .map(|name| cone_of_influence(&fan_in_map(&variables), &input_variables, &name));
+        dump_dot(&variables, &gates, &input_variables, highlight.as_ref());
         println!("Challenge 2: {}", (flatten_switches(&switches)).join(","));
     }
+
+    if env::args().any(|arg| arg == "--json") {
+        let circuit = describe_circuit(&variables, &gates, &input_variables);
+        write(
+            "circuit.json",
+            serde_json::to_string_pretty(&circuit).unwrap(),
+        )
+        .unwrap();
+        let report = simulation_report(&variables, Some(check_passed));
+        write(
+            "report.json",
+            serde_json::to_string_pretty(&report).unwrap(),
+        )
+        .unwrap();
+    }
 }
@@ -0,0 +1,248 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+/// Sorted variable indices of a product term, Boolean variables satisfy `v * v = v`
+/// so every variable occurs at most once
+pub type Monomial = Vec<usize>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoefficientOverflow;
+
+impl fmt::Display for CoefficientOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Polynomial coefficient overflow")
+    }
+}
+
+/// Multilinear polynomial with integer coefficients over Boolean variables
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    terms: BTreeMap<Monomial, i128>,
+}
+
+impl Polynomial {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn constant(value: i128) -> Self {
+        let mut result = Self::zero();
+        result.add_term(Vec::new(), value);
+        result
+    }
+
+    pub fn variable(index: usize) -> Self {
+        let mut result = Self::zero();
+        result.add_term(vec![index], 1);
+        result
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn num_terms(&self) -> usize {
+        self.terms.len()
+    }
+
+    fn add_term(&mut self, monomial: Monomial, coefficient: i128) {
+        self.try_add_term(monomial, coefficient)
+            .expect("Coefficient overflow");
+    }
+
+    fn try_add_term(
+        &mut self,
+        monomial: Monomial,
+        coefficient: i128,
+    ) -> Result<(), CoefficientOverflow> {
+        match self.terms.entry(monomial) {
+            Entry::Occupied(mut entry) => {
+                let sum = entry
+                    .get()
+                    .checked_add(coefficient)
+                    .ok_or(CoefficientOverflow)?;
+                if sum == 0 {
+                    entry.remove();
+                } else {
+                    *entry.get_mut() = sum;
+                }
+            }
+            Entry::Vacant(entry) => {
+                if coefficient != 0 {
+                    entry.insert(coefficient);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn scale(&self, factor: i128) -> Self {
+        let mut result = Self::zero();
+        for (monomial, coefficient) in &self.terms {
+            result.add_term(
+                monomial.clone(),
+                coefficient
+                    .checked_mul(factor)
+                    .expect("Coefficient overflow"),
+            );
+        }
+        result
+    }
+
+    /// Replaces every occurrence of the variable `index` by `replacement`
+    pub fn substitute(
+        &self,
+        index: usize,
+        replacement: &Polynomial,
+    ) -> Result<Self, CoefficientOverflow> {
+        let mut untouched = Self::zero();
+        let mut factor = Self::zero();
+
+        for (monomial, coefficient) in &self.terms {
+            if let Ok(pos) = monomial.binary_search(&index) {
+                let mut rest = monomial.clone();
+                rest.remove(pos);
+                factor.add_term(rest, *coefficient);
+            } else {
+                untouched.add_term(monomial.clone(), *coefficient);
+            }
+        }
+
+        if factor.is_zero() {
+            return Ok(untouched);
+        }
+        for (monomial, coefficient) in (&factor * replacement)?.terms {
+            untouched.try_add_term(monomial, coefficient)?;
+        }
+        Ok(untouched)
+    }
+
+    /// Drops all terms containing both variables, valid whenever their product is known to vanish
+    pub fn remove_vanishing(&mut self, index_1: usize, index_2: usize) {
+        self.terms.retain(|monomial, _| {
+            monomial.binary_search(&index_1).is_err() || monomial.binary_search(&index_2).is_err()
+        });
+    }
+
+    pub fn format_with_names(&self, names: &[String]) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        self.terms
+            .iter()
+            .map(|(monomial, coefficient)| {
+                let factors: Vec<&str> = monomial.iter().map(|idx| names[*idx].as_str()).collect();
+                match (factors.is_empty(), *coefficient) {
+                    (true, c) => c.to_string(),
+                    (false, 1) => factors.join("*"),
+                    (false, c) => format!("{}*{}", c, factors.join("*")),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, other: &Polynomial) -> Polynomial {
+        let mut result = self.clone();
+        for (monomial, coefficient) in &other.terms {
+            result.add_term(monomial.clone(), *coefficient);
+        }
+        result
+    }
+}
+
+impl Sub for &Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, other: &Polynomial) -> Polynomial {
+        self + &other.scale(-1)
+    }
+}
+
+/// Fails instead of panicking if a coefficient overflows, as products of wide words can
+impl Mul for &Polynomial {
+    type Output = Result<Polynomial, CoefficientOverflow>;
+
+    fn mul(self, other: &Polynomial) -> Result<Polynomial, CoefficientOverflow> {
+        let mut result = Polynomial::zero();
+        for (monomial_1, coefficient_1) in &self.terms {
+            for (monomial_2, coefficient_2) in &other.terms {
+                let mut monomial: Monomial = monomial_1
+                    .iter()
+                    .chain(monomial_2.iter())
+                    .cloned()
+                    .collect();
+                monomial.sort();
+                monomial.dedup();
+                result.try_add_term(
+                    monomial,
+                    coefficient_1
+                        .checked_mul(*coefficient_2)
+                        .ok_or(CoefficientOverflow)?,
+                )?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_boolean_reduction() {
+        let a = Polynomial::variable(0);
+        assert_eq!(&a * &a, Ok(a));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let a = Polynomial::variable(0).scale(1 << 64);
+        let b = Polynomial::variable(1).scale(1 << 63);
+        assert_eq!(&a * &b, Err(CoefficientOverflow));
+        assert!((&a * &Polynomial::variable(1).scale(1 << 62)).is_ok());
+    }
+
+    #[test]
+    fn test_xor_identity() {
+        // a XOR b = a + b - 2ab, and (a XOR b) XOR b = a
+        let a = Polynomial::variable(0);
+        let b = Polynomial::variable(1);
+        let xor = |p: &Polynomial, q: &Polynomial| &(p + q) - &(p * q).unwrap().scale(2);
+        assert_eq!(xor(&xor(&a, &b), &b), a);
+    }
+
+    #[test]
+    fn test_substitute() {
+        let a = Polynomial::variable(0);
+        let b = Polynomial::variable(1);
+        let c = Polynomial::variable(2);
+        let ab = (&a * &b).unwrap();
+        let ac = (&a * &c).unwrap();
+        let polynomial = &ab + &Polynomial::constant(3);
+        assert_eq!(
+            polynomial.substitute(1, &(&b + &c)),
+            Ok(&(&ab + &ac) + &Polynomial::constant(3))
+        );
+        assert_eq!((&a - &a).num_terms(), 0);
+    }
+
+    #[test]
+    fn test_remove_vanishing() {
+        let a = Polynomial::variable(0);
+        let b = Polynomial::variable(1);
+        let c = Polynomial::variable(2);
+        let ac = (&a * &c).unwrap();
+        let mut polynomial = &(&(&a * &b).unwrap() * &c).unwrap() + &ac;
+        polynomial.remove_vanishing(1, 0);
+        assert_eq!(polynomial, ac);
+    }
+}